@@ -0,0 +1,93 @@
+use crate::{strip_bom, Context, ParseConfig, ParseError, Value};
+
+/// Parses a sequence of JSON values that follow each other without any
+/// delimiter, such as `{"a":1}{"b":2}`.
+///
+/// Whitespace between values is skipped. The first error ends iteration,
+/// since there is no telling where the next value would start. Error
+/// positions refer to the whole of `src`.
+pub fn parse_all(src: &str) -> ParseAll<'_> {
+    ParseAll::new(src)
+}
+
+/// Iterator over a sequence of concatenated values, see [`parse_all`].
+#[derive(Debug, Clone)]
+pub struct ParseAll<'a> {
+    src: &'a str,
+    /// The input not yet parsed, or `None` after an error.
+    remaining: Option<&'a str>,
+}
+
+impl<'a> ParseAll<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            remaining: Some(strip_bom(src)),
+        }
+    }
+}
+
+impl<'a> Iterator for ParseAll<'a> {
+    type Item = Result<Value<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = self.remaining?;
+
+        match Context::new(self.src, &ParseConfig::default()).parse_one(remaining) {
+            Ok(Some((value, rest))) => {
+                self.remaining = Some(rest);
+                Some(Ok(value))
+            }
+            Ok(None) => {
+                self.remaining = None;
+                None
+            }
+            Err(error) => {
+                self.remaining = None;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Map;
+
+    #[test]
+    fn concatenated_values() {
+        let values: Vec<_> = parse_all("{\"a\":1}{\"b\":2}  [3]\n\"x\"4 null").collect();
+        assert_eq!(values.len(), 6);
+        assert_eq!(values[1].as_ref().unwrap()["b"], Value::Integer(2));
+        assert_eq!(values[3], Ok(Value::String("x".into())));
+        assert_eq!(values[4], Ok(Value::Integer(4)));
+        assert_eq!(values[5], Ok(Value::Null));
+
+        assert_eq!(parse_all(" \n ").count(), 0);
+
+        let values: Vec<_> = parse_all("null[1] true{}false\"a\"").collect();
+        assert_eq!(
+            values,
+            [
+                Ok(Value::Null),
+                Ok(Value::Array(vec![Value::Integer(1)])),
+                Ok(Value::Bool(true)),
+                Ok(Value::Object(Map::new())),
+                Ok(Value::Bool(false)),
+                Ok(Value::String("a".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn error_ends_iteration() {
+        let mut values = parse_all("1 [2, 3 4");
+        assert_eq!(values.next(), Some(Ok(Value::Integer(1))));
+
+        let error = values.next().unwrap().unwrap_err();
+        assert_eq!(error.message, "expected ',' or ']' after array element");
+        assert_eq!(error.byte_offset, 8);
+        assert_eq!(values.next(), None);
+    }
+}
@@ -0,0 +1,163 @@
+/// Options controlling what [`parse_with_config`](crate::parse_with_config)
+/// accepts.
+///
+/// The default accepts exactly what [`parse`](crate::parse) does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseConfig {
+    /// What to do when an object contains the same key more than once.
+    pub duplicate_key_behavior: DuplicateKeyBehavior,
+    /// How many arrays and objects may be nested inside each other before
+    /// parsing fails with
+    /// [`ErrorKind::DepthLimitExceeded`](crate::ErrorKind::DepthLimitExceeded).
+    ///
+    /// Defaults to 128. The parser doesn't recurse, so large limits only cost
+    /// heap memory.
+    pub max_depth: usize,
+    /// Whether a single `,` may follow the last element of an array or
+    /// object, as in `[1, 2,]`. Off by default, as RFC 8259 forbids it.
+    pub allow_trailing_commas: bool,
+    /// Whether `//` line comments and `/* */` block comments may appear
+    /// wherever whitespace can, as in JSONC. Off by default, as RFC 8259
+    /// forbids them.
+    pub allow_comments: bool,
+    /// Whether the number literals `NaN`, `Infinity` and `-Infinity` are
+    /// accepted, as written by Python's `json` module among others. Off by
+    /// default, as RFC 8259 forbids them.
+    ///
+    /// Also decides what happens to a number too large for an `f64`, such
+    /// as `1e400`: with this option it becomes infinite, without it parsing
+    /// fails.
+    ///
+    /// Serializing still writes non-finite numbers as `null`.
+    pub allow_non_finite: bool,
+    /// Whether numbers are kept as the text of their literal, as
+    /// [`Value::RawNumber`](crate::Value::RawNumber), so that serializing
+    /// reproduces them byte for byte: `1.0` stays `1.0` and `1e2` stays
+    /// `1e2`. Numbers too large for an `f64`, like `1e400`, are kept too.
+    /// Off by default. `NaN` and the infinities are still parsed as numbers.
+    pub raw_numbers: bool,
+    /// The longest input, in bytes, that is parsed at all. Longer input
+    /// fails with [`ErrorKind::InputTooLarge`](crate::ErrorKind::InputTooLarge)
+    /// before any of it is looked at. Unlimited by default.
+    pub max_input_bytes: Option<usize>,
+    /// The longest string or object key, in bytes after decoding escapes.
+    /// Longer ones fail with
+    /// [`ErrorKind::StringTooLong`](crate::ErrorKind::StringTooLong).
+    /// Unlimited by default.
+    pub max_string_bytes: Option<usize>,
+}
+
+impl Default for ParseConfig {
+    fn default() -> Self {
+        Self {
+            duplicate_key_behavior: DuplicateKeyBehavior::default(),
+            max_depth: 128,
+            allow_trailing_commas: false,
+            allow_comments: false,
+            allow_non_finite: false,
+            raw_numbers: false,
+            max_input_bytes: None,
+            max_string_bytes: None,
+        }
+    }
+}
+
+/// Builder-style setters, e.g.
+/// `ParseConfig::new().allow_comments(true).max_depth(32)`.
+impl ParseConfig {
+    /// Same as [`ParseConfig::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn duplicate_key_behavior(mut self, behavior: DuplicateKeyBehavior) -> Self {
+        self.duplicate_key_behavior = behavior;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn allow_trailing_commas(mut self, allow: bool) -> Self {
+        self.allow_trailing_commas = allow;
+        self
+    }
+
+    pub fn allow_comments(mut self, allow: bool) -> Self {
+        self.allow_comments = allow;
+        self
+    }
+
+    pub fn allow_non_finite(mut self, allow: bool) -> Self {
+        self.allow_non_finite = allow;
+        self
+    }
+
+    pub fn raw_numbers(mut self, raw: bool) -> Self {
+        self.raw_numbers = raw;
+        self
+    }
+
+    pub fn max_input_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_input_bytes = Some(max_bytes);
+        self
+    }
+
+    pub fn max_string_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_string_bytes = Some(max_bytes);
+        self
+    }
+}
+
+/// Another name for [`ParseConfig`].
+pub type ParseOptions = ParseConfig;
+
+/// Options controlling how [`Value::to_string_with`](crate::Value::to_string_with)
+/// prints a value.
+///
+/// The default prints the same as
+/// [`Value::to_json_string`](crate::Value::to_json_string).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteConfig {
+    /// Written once per nesting level in front of every array element and
+    /// object entry, each on its own line, as by
+    /// [`Value::to_json_pretty`](crate::Value::to_json_pretty). Empty by
+    /// default, for compact output.
+    pub indent: String,
+    /// Whether to escape every character outside of ASCII as `\uXXXX`, as
+    /// a surrogate pair if needed, for transports that can't carry UTF-8.
+    /// Off by default.
+    pub ascii_only: bool,
+}
+
+/// Builder-style setters, e.g. `WriteConfig::new().indent("  ")`.
+impl WriteConfig {
+    /// Same as [`WriteConfig::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = indent.into();
+        self
+    }
+
+    pub fn ascii_only(mut self, ascii_only: bool) -> Self {
+        self.ascii_only = ascii_only;
+        self
+    }
+}
+
+/// How to handle a key that appears more than once in the same object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeyBehavior {
+    /// Keep the value of the last occurrence.
+    #[default]
+    UseLast,
+    /// Keep the value of the first occurrence and ignore the later ones.
+    UseFirst,
+    /// Fail with an error pointing at the repeated key.
+    Error,
+}
@@ -0,0 +1,125 @@
+use std::hash::{Hash, Hasher};
+
+use crate::{OwnedValue, Value};
+
+/// Compares like `==` on `f64`, except that `NaN` equals itself, so that
+/// the comparison is reflexive and values can be `Eq`.
+fn number_eq(a: f64, b: f64) -> bool {
+    a == b || (a.is_nan() && b.is_nan())
+}
+
+/// Hashes `n` consistently with [`number_eq`]: `-0.0` like `0.0`, and every
+/// `NaN` alike.
+fn hash_number(n: f64, state: &mut impl Hasher) {
+    let n = if n == 0.0 {
+        0.0
+    } else if n.is_nan() {
+        f64::NAN
+    } else {
+        n
+    };
+    n.to_bits().hash(state);
+}
+
+/// Implements `PartialEq`, `Eq` and `Hash` for one of the value enums, which
+/// share their variants.
+macro_rules! impl_eq_hash {
+    ($value:ident $(<$lt:lifetime>)?) => {
+        impl PartialEq for $value$(<$lt>)? {
+            fn eq(&self, other: &Self) -> bool {
+                match (self, other) {
+                    (Self::Null, Self::Null) => true,
+                    (Self::Bool(a), Self::Bool(b)) => a == b,
+                    (Self::Number(a), Self::Number(b)) => number_eq(*a, *b),
+                    (Self::Integer(a), Self::Integer(b)) => a == b,
+                    (Self::BigInteger(a), Self::BigInteger(b)) => a == b,
+                    (Self::RawNumber(a), Self::RawNumber(b)) => a == b,
+                    (Self::String(a), Self::String(b)) => a == b,
+                    (Self::Array(a), Self::Array(b)) => a == b,
+                    (Self::Object(a), Self::Object(b)) => a == b,
+                    _ => false,
+                }
+            }
+        }
+
+        impl Eq for $value$(<$lt>)? {}
+
+        impl Hash for $value$(<$lt>)? {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                std::mem::discriminant(self).hash(state);
+                match self {
+                    Self::Null => {}
+                    Self::Bool(b) => b.hash(state),
+                    Self::Number(n) => hash_number(*n, state),
+                    Self::Integer(n) => n.hash(state),
+                    Self::BigInteger(n) => n.hash(state),
+                    Self::RawNumber(raw) => raw.hash(state),
+                    Self::String(s) => s.hash(state),
+                    Self::Array(arr) => arr.hash(state),
+                    Self::Object(map) => map.hash(state),
+                }
+            }
+        }
+    };
+}
+
+impl_eq_hash!(Value<'_>);
+impl_eq_hash!(OwnedValue);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::parse_complete;
+
+    fn hash_of(value: &impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(Value::Number(f64::NAN), Value::Number(-f64::NAN));
+        assert_eq!(
+            hash_of(&Value::Number(f64::NAN)),
+            hash_of(&Value::Number(-f64::NAN))
+        );
+        assert_eq!(Value::Number(0.0), Value::Number(-0.0));
+        assert_eq!(hash_of(&Value::Number(0.0)), hash_of(&Value::Number(-0.0)));
+        assert_ne!(Value::Number(1.0), Value::Integer(1));
+        assert_ne!(Value::Number(f64::NAN), Value::Null);
+    }
+
+    #[test]
+    fn objects_ignore_key_order() {
+        let a = parse_complete(r#"{"a": 1, "b": [2, {"c": null, "d": true}]}"#).unwrap();
+        let b = parse_complete(r#"{"b": [2, {"d": true, "c": null}], "a": 1}"#).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+
+        let c = parse_complete(r#"{"a": 1, "b": [{"c": null, "d": true}, 2]}"#).unwrap();
+        assert_ne!(a, c);
+        assert_ne!(hash_of(&a), hash_of(&c));
+    }
+
+    #[test]
+    fn dedupe_in_a_set() {
+        let records = [
+            r#"{"id": 1, "tags": ["x"]}"#,
+            r#"{"tags": ["x"], "id": 1}"#,
+            r#"{"id": 2, "tags": []}"#,
+            r#"{"id": 1.0, "tags": ["x"]}"#,
+        ];
+        let set: HashSet<_> = records
+            .iter()
+            .map(|src| parse_complete(src).unwrap())
+            .collect();
+        assert_eq!(set.len(), 3);
+
+        let owned: HashSet<OwnedValue> = records.iter().map(|src| src.parse().unwrap()).collect();
+        assert_eq!(owned.len(), 3);
+    }
+}
@@ -0,0 +1,118 @@
+use std::{fmt, io};
+
+/// Error returned when the input is not valid JSON, or could not be read.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseError {
+    pub kind: ErrorKind,
+    /// Description of what went wrong.
+    pub message: String,
+    /// Byte offset into the source at which the error was detected.
+    pub byte_offset: usize,
+    /// 1-indexed line of `byte_offset`, or 0 when the input is not available
+    /// as text.
+    pub line: usize,
+    /// 1-indexed column of `byte_offset`, counted in characters, or 0 when
+    /// the input is not available as text.
+    pub column: usize,
+    /// The character found at `byte_offset`, or `None` at the end of the input.
+    pub found: Option<char>,
+}
+
+impl ParseError {
+    pub(crate) fn new(message: impl Into<String>, src: &str, byte_offset: usize) -> Self {
+        let (line, column) = position_of(src, byte_offset);
+        Self {
+            kind: ErrorKind::Syntax,
+            message: message.into(),
+            byte_offset,
+            line,
+            column,
+            found: src[byte_offset..].chars().next(),
+        }
+    }
+
+    pub(crate) fn with_kind(self, kind: ErrorKind) -> Self {
+        Self { kind, ..self }
+    }
+
+    /// An error for input that was never turned into text.
+    fn without_position(kind: ErrorKind, message: String, byte_offset: usize) -> Self {
+        Self {
+            kind,
+            message,
+            byte_offset,
+            line: 0,
+            column: 0,
+            found: None,
+        }
+    }
+
+    pub(crate) fn io(error: io::Error) -> Self {
+        Self::without_position(ErrorKind::Io(error.kind()), error.to_string(), 0)
+    }
+
+    pub(crate) fn input_too_large(limit: usize) -> Self {
+        Self::without_position(
+            ErrorKind::InputTooLarge,
+            format!("input is larger than {limit} bytes"),
+            0,
+        )
+    }
+}
+
+/// The category of a [`ParseError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The input is not valid JSON.
+    Syntax,
+    /// Reading the input failed.
+    Io(io::ErrorKind),
+    /// The input is longer than the configured limit.
+    InputTooLarge,
+    /// Arrays and objects are nested deeper than the configured limit.
+    DepthLimitExceeded,
+    /// A string or object key is longer than the configured limit.
+    StringTooLong,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Syntax | ErrorKind::DepthLimitExceeded | ErrorKind::StringTooLong => write!(
+                f,
+                "parse error at line {}, column {}: {}",
+                self.line, self.column, self.message
+            ),
+            ErrorKind::Io(_) => write!(f, "I/O error: {}", self.message),
+            ErrorKind::InputTooLarge => write!(f, "parse error: {}", self.message),
+        }
+    }
+}
+
+/// Returns the 1-indexed `(line, column)` of the byte `offset` in `src`.
+///
+/// `\n`, `\r\n` and a lone `\r` each end a line. Columns are counted in
+/// characters. Offsets past the end of `src` are clamped to its end.
+pub fn position_of(src: &str, offset: usize) -> (usize, usize) {
+    let before = String::from_utf8_lossy(&src.as_bytes()[..offset.min(src.len())]);
+    let (mut line, mut column) = (1, 1);
+    let mut after_cr = false;
+
+    for c in before.chars() {
+        match c {
+            // The line already ended at the `\r`.
+            '\n' if after_cr => {}
+            '\n' | '\r' => {
+                line += 1;
+                column = 1;
+            }
+            _ => column += 1,
+        }
+        after_cr = c == '\r';
+    }
+
+    (line, column)
+}
+
+impl std::error::Error for ParseError {}
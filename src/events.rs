@@ -0,0 +1,240 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::{strip_bom, Context, ParseConfig, ParseError, Value};
+
+/// Something [`parse_events`] found in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'a> {
+    StartObject,
+    /// An object key. The next events describe its value.
+    Key(Cow<'a, str>),
+    EndObject,
+    StartArray,
+    EndArray,
+    /// A value that is neither an array nor an object.
+    Value(Value<'a>),
+}
+
+/// Parses `src` as a single JSON document like
+/// [`parse_complete`](crate::parse_complete), but hands `handler` an
+/// [`Event`] for each part of it instead of building a [`Value`].
+///
+/// Memory use only depends on how deeply the input nests, so this suits
+/// documents too large to hold as a tree. Events are emitted as soon as they
+/// are parsed, so `handler` may see some before an error is returned. Keys
+/// are reported as they appear, repeated ones included.
+pub fn parse_events<'a>(src: &'a str, handler: impl FnMut(Event<'a>)) -> Result<(), ParseError> {
+    let mut handler = handler;
+    Context::new(src, &ParseConfig::default())
+        .parse_events(strip_bom(src), |event, _| handler(event))
+}
+
+/// An array or object whose elements are still being parsed.
+#[derive(Clone, Copy)]
+enum Container {
+    Array,
+    Object,
+}
+
+impl<'a> Context<'a, '_> {
+    /// Like `parse_complete`, see [`parse_events`]. `handler` also gets the
+    /// byte range of the token behind each event: the bracket for the start
+    /// and end of a container, and the whole key or scalar otherwise.
+    pub(crate) fn parse_events(
+        &self,
+        src: &'a str,
+        mut handler: impl FnMut(Event<'a>, Range<usize>),
+    ) -> Result<(), ParseError> {
+        self.check_input_size()?;
+        let span = |token: &str, rest: &str| self.offset(token)..self.offset(rest);
+
+        let mut stack = Vec::new();
+        let mut remaining = self.skip_whitespace_and_comments(src)?;
+
+        loop {
+            match remaining.as_bytes().first() {
+                Some(b'[') => {
+                    self.check_depth(remaining, stack.len() + 1)?;
+                    handler(Event::StartArray, span(remaining, &remaining[1..]));
+                    remaining = self.skip_whitespace_and_comments(&remaining[1..])?;
+
+                    match remaining.strip_prefix(']') {
+                        Some(rest) => {
+                            handler(Event::EndArray, span(remaining, rest));
+                            remaining = rest;
+                        }
+                        None => {
+                            stack.push(Container::Array);
+                            continue;
+                        }
+                    }
+                }
+                Some(b'{') => {
+                    self.check_depth(remaining, stack.len() + 1)?;
+                    handler(Event::StartObject, span(remaining, &remaining[1..]));
+                    remaining = self.skip_whitespace_and_comments(&remaining[1..])?;
+
+                    match remaining.strip_prefix('}') {
+                        Some(rest) => {
+                            handler(Event::EndObject, span(remaining, rest));
+                            remaining = rest;
+                        }
+                        None => {
+                            let (key, raw, rest) = self.parse_key(remaining)?;
+                            handler(Event::Key(key), span(raw, &raw[raw.len()..]));
+                            remaining = rest;
+                            stack.push(Container::Object);
+                            continue;
+                        }
+                    }
+                }
+                _ => {
+                    let (value, rest) = self.parse_scalar(remaining)?;
+                    handler(Event::Value(value), span(remaining, rest));
+                    remaining = rest;
+                }
+            }
+
+            // A value just ended. Close containers until one continues with
+            // another element.
+            loop {
+                remaining = self.skip_whitespace_and_comments(remaining)?;
+
+                let Some(&container) = stack.last() else {
+                    if !remaining.is_empty() {
+                        return Err(self.error(remaining, "unexpected trailing characters"));
+                    }
+                    return Ok(());
+                };
+                let (close, end, expected) = match container {
+                    Container::Array => (
+                        ']',
+                        Event::EndArray,
+                        "expected ',' or ']' after array element",
+                    ),
+                    Container::Object => (
+                        '}',
+                        Event::EndObject,
+                        "expected ',' or '}' after object value",
+                    ),
+                };
+
+                if let Some(rest) = remaining.strip_prefix(',') {
+                    let next = self.skip_whitespace_and_comments(rest)?;
+                    if let Some(rest) = self.strip_trailing_close(next, close) {
+                        handler(end, span(next, rest));
+                        remaining = rest;
+                    } else {
+                        remaining = next;
+                        if let Container::Object = container {
+                            let (key, raw, rest) = self.parse_key(remaining)?;
+                            handler(Event::Key(key), span(raw, &raw[raw.len()..]));
+                            remaining = rest;
+                        }
+                        break;
+                    }
+                } else if let Some(rest) = remaining.strip_prefix(close) {
+                    handler(end, span(remaining, rest));
+                    remaining = rest;
+                } else {
+                    return Err(self.error(remaining, expected));
+                }
+
+                stack.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    fn events(src: &str) -> Result<Vec<Event<'_>>, ParseError> {
+        let mut events = Vec::new();
+        parse_events(src, |event| events.push(event))?;
+        Ok(events)
+    }
+
+    #[test]
+    fn nested_document() {
+        use Event::*;
+
+        assert_eq!(
+            events(r#" {"a": [1, {"b": null}, []], "c": {}, "d": "e\n"} "#),
+            Ok(vec![
+                StartObject,
+                Key("a".into()),
+                StartArray,
+                Value(crate::Value::Integer(1)),
+                StartObject,
+                Key("b".into()),
+                Value(crate::Value::Null),
+                EndObject,
+                StartArray,
+                EndArray,
+                EndArray,
+                Key("c".into()),
+                StartObject,
+                EndObject,
+                Key("d".into()),
+                Value(crate::Value::String("e\n".into())),
+                EndObject,
+            ])
+        );
+        assert_eq!(events("true"), Ok(vec![Value(crate::Value::Bool(true))]));
+    }
+
+    #[test]
+    fn extract_a_field() {
+        let src = r#"[{"id": 1, "name": "a"}, {"id": 2, "name": "b", "tags": ["name"]}]"#;
+        let mut names = Vec::new();
+        let mut in_name = false;
+        parse_events(src, |event| match event {
+            Event::Key(key) => in_name = key == "name",
+            Event::Value(Value::String(name)) if in_name => names.push(name),
+            _ => in_name = false,
+        })
+        .unwrap();
+        assert_eq!(names, ["a", "b"]);
+    }
+
+    #[test]
+    fn errors() {
+        for (src, message, offset) in [
+            ("", "unexpected end of input", 0),
+            ("[1 2]", "expected ',' or ']' after array element", 3),
+            (
+                "{\"a\": 1 \"b\"}",
+                "expected ',' or '}' after object value",
+                8,
+            ),
+            ("{\"a\" 1}", "expected ':' after object key", 5),
+            ("[1,]", "unexpected character ']'", 3),
+            ("{} []", "unexpected trailing characters", 3),
+            ("[[]", "expected ',' or ']' after array element", 3),
+        ] {
+            let error = events(src).unwrap_err();
+            assert_eq!(
+                (error.message.as_str(), error.byte_offset),
+                (message, offset),
+                "{src}"
+            );
+        }
+
+        let deep = "[".repeat(1000);
+        assert_eq!(
+            events(&deep).unwrap_err().kind,
+            ErrorKind::DepthLimitExceeded
+        );
+    }
+
+    #[test]
+    fn events_before_an_error() {
+        let mut seen = 0;
+        assert!(parse_events("[1, 2, x]", |_| seen += 1).is_err());
+        assert_eq!(seen, 3);
+    }
+}
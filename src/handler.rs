@@ -0,0 +1,353 @@
+use crate::patch::child_path;
+use crate::{strip_bom, Context, Event, Map, OwnedValue, ParseConfig, ParseError, Value};
+
+/// Callbacks for [`parse_with_handler`], called while the input is parsed.
+///
+/// These are the methods of [`Visitor`](crate::Visitor), except that the
+/// length of an array or object isn't known yet when it starts. Every
+/// method does nothing by default.
+pub trait JsonHandler {
+    fn visit_null(&mut self) {}
+
+    fn visit_bool(&mut self, _b: bool) {}
+
+    /// Called for numbers, and for integers unless
+    /// [`JsonHandler::visit_integer`] is overridden.
+    fn visit_number(&mut self, _n: f64) {}
+
+    /// Called for numbers that parse as [`Value::Integer`]. Passes them on
+    /// to [`JsonHandler::visit_number`] by default.
+    fn visit_integer(&mut self, n: i64) {
+        self.visit_number(n as f64);
+    }
+
+    /// Called for numbers that parse as [`Value::BigInteger`]. Passes them on
+    /// to [`JsonHandler::visit_number`] by default.
+    fn visit_big_integer(&mut self, n: i128) {
+        self.visit_number(n as f64);
+    }
+
+    fn visit_string(&mut self, _s: &str) {}
+
+    fn visit_array_start(&mut self) {}
+
+    fn visit_array_end(&mut self) {}
+
+    fn visit_object_start(&mut self) {}
+
+    /// Called before the value of each object entry.
+    fn visit_key(&mut self, _k: &str) {}
+
+    fn visit_object_end(&mut self) {}
+}
+
+/// Parses `src` as a single JSON document like
+/// [`parse_complete`](crate::parse_complete), calling the methods of
+/// `handler` for each node as it is parsed instead of building a [`Value`].
+///
+/// Like [`parse_events`](crate::parse_events), `handler` may have seen part
+/// of the document when an error is returned.
+pub fn parse_with_handler<H: JsonHandler>(src: &str, handler: &mut H) -> Result<(), ParseError> {
+    Context::new(src, &ParseConfig::default()).parse_events(
+        strip_bom(src),
+        |event, _| match event {
+            Event::StartObject => handler.visit_object_start(),
+            Event::Key(k) => handler.visit_key(&k),
+            Event::EndObject => handler.visit_object_end(),
+            Event::StartArray => handler.visit_array_start(),
+            Event::EndArray => handler.visit_array_end(),
+            Event::Value(Value::Null) => handler.visit_null(),
+            Event::Value(Value::Bool(b)) => handler.visit_bool(b),
+            Event::Value(Value::Number(n)) => handler.visit_number(n),
+            Event::Value(Value::Integer(n)) => handler.visit_integer(n),
+            Event::Value(Value::BigInteger(n)) => handler.visit_big_integer(n),
+            Event::Value(Value::String(s)) => handler.visit_string(&s),
+            Event::Value(Value::Array(_) | Value::Object(_)) => {
+                unreachable!("containers are reported as start and end events")
+            }
+            Event::Value(Value::RawNumber(_)) => unreachable!("raw numbers are off by default"),
+        },
+    )
+}
+
+/// A [`JsonHandler`] that counts the values in a document, containers
+/// included.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CountingHandler {
+    pub nodes: usize,
+}
+
+impl JsonHandler for CountingHandler {
+    fn visit_null(&mut self) {
+        self.nodes += 1;
+    }
+
+    fn visit_bool(&mut self, _b: bool) {
+        self.nodes += 1;
+    }
+
+    fn visit_number(&mut self, _n: f64) {
+        self.nodes += 1;
+    }
+
+    fn visit_string(&mut self, _s: &str) {
+        self.nodes += 1;
+    }
+
+    fn visit_array_start(&mut self) {
+        self.nodes += 1;
+    }
+
+    fn visit_object_start(&mut self) {
+        self.nodes += 1;
+    }
+}
+
+/// A [`JsonHandler`] that builds only the values whose JSON Pointer
+/// `predicate` accepts, such as `/users/3/name`.
+///
+/// Values nested in a selected one are part of it and not offered to
+/// `predicate` on their own.
+pub struct SelectingHandler<F> {
+    predicate: F,
+    /// JSON Pointer to the value being parsed.
+    path: String,
+    /// The arrays and objects that are open.
+    levels: Vec<Level>,
+    /// Whether a selected value is being built.
+    selecting: bool,
+    /// The open arrays and objects of the value being built.
+    partials: Vec<Partial>,
+    selected: Vec<(String, OwnedValue)>,
+}
+
+struct Level {
+    /// The length of the container's JSON Pointer in `path`.
+    path_len: usize,
+    /// The index of the next element, for arrays.
+    next_index: Option<usize>,
+}
+
+enum Partial {
+    Array(Vec<OwnedValue>),
+    Object {
+        map: Map<String, OwnedValue>,
+        /// The key of the entry being parsed.
+        key: String,
+    },
+}
+
+impl<F: FnMut(&str) -> bool> SelectingHandler<F> {
+    pub fn new(predicate: F) -> Self {
+        Self {
+            predicate,
+            path: String::new(),
+            levels: Vec::new(),
+            selecting: false,
+            partials: Vec::new(),
+            selected: Vec::new(),
+        }
+    }
+
+    /// The selected values so far and their JSON Pointers, in document
+    /// order.
+    pub fn selected(&self) -> &[(String, OwnedValue)] {
+        &self.selected
+    }
+
+    pub fn into_selected(self) -> Vec<(String, OwnedValue)> {
+        self.selected
+    }
+
+    /// Points `path` at the value that starts next and checks whether to
+    /// select it.
+    fn begin(&mut self) {
+        match self.levels.last_mut() {
+            Some(Level {
+                path_len,
+                next_index: Some(index),
+            }) => {
+                self.path.truncate(*path_len);
+                self.path = child_path(&self.path, &index.to_string());
+                *index += 1;
+            }
+            // `visit_key` has set the path of an object entry.
+            Some(_) => {}
+            None => self.path.clear(),
+        }
+
+        if !self.selecting {
+            self.selecting = (self.predicate)(&self.path);
+        }
+    }
+
+    fn scalar(&mut self, value: OwnedValue) {
+        self.begin();
+        if self.selecting {
+            self.finish(value);
+        }
+    }
+
+    fn start(&mut self, partial: Partial) {
+        self.begin();
+        let next_index = match partial {
+            Partial::Array(_) => Some(0),
+            Partial::Object { .. } => None,
+        };
+        self.levels.push(Level {
+            path_len: self.path.len(),
+            next_index,
+        });
+        if self.selecting {
+            self.partials.push(partial);
+        }
+    }
+
+    fn end(&mut self) {
+        if let Some(level) = self.levels.pop() {
+            self.path.truncate(level.path_len);
+        }
+        if let Some(partial) = self.partials.pop() {
+            self.finish(match partial {
+                Partial::Array(elements) => OwnedValue::Array(elements),
+                Partial::Object { map, .. } => OwnedValue::Object(map),
+            });
+        }
+    }
+
+    /// Adds a complete value to the one being built, or selects it if it is
+    /// the whole of it.
+    fn finish(&mut self, value: OwnedValue) {
+        match self.partials.last_mut() {
+            Some(Partial::Array(elements)) => elements.push(value),
+            Some(Partial::Object { map, key }) => {
+                map.insert(std::mem::take(key), value);
+            }
+            None => {
+                self.selected.push((self.path.clone(), value));
+                self.selecting = false;
+            }
+        }
+    }
+}
+
+impl<F: FnMut(&str) -> bool> JsonHandler for SelectingHandler<F> {
+    fn visit_null(&mut self) {
+        self.scalar(OwnedValue::Null);
+    }
+
+    fn visit_bool(&mut self, b: bool) {
+        self.scalar(OwnedValue::Bool(b));
+    }
+
+    fn visit_number(&mut self, n: f64) {
+        self.scalar(OwnedValue::Number(n));
+    }
+
+    fn visit_integer(&mut self, n: i64) {
+        self.scalar(OwnedValue::Integer(n));
+    }
+
+    fn visit_big_integer(&mut self, n: i128) {
+        self.scalar(OwnedValue::BigInteger(n));
+    }
+
+    fn visit_string(&mut self, s: &str) {
+        self.scalar(OwnedValue::String(s.to_owned()));
+    }
+
+    fn visit_array_start(&mut self) {
+        self.start(Partial::Array(Vec::new()));
+    }
+
+    fn visit_array_end(&mut self) {
+        self.end();
+    }
+
+    fn visit_object_start(&mut self) {
+        self.start(Partial::Object {
+            map: Map::new(),
+            key: String::new(),
+        });
+    }
+
+    fn visit_key(&mut self, k: &str) {
+        if let Some(level) = self.levels.last() {
+            self.path.truncate(level.path_len);
+            self.path = child_path(&self.path, k);
+        }
+        if let Some(Partial::Object { key, .. }) = self.partials.last_mut() {
+            k.clone_into(key);
+        }
+    }
+
+    fn visit_object_end(&mut self) {
+        self.end();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_complete;
+
+    const SRC: &str = r#"{
+        "users": [
+            {"name": "ada", "tags": ["a", "b"], "meta": {"name": "inner"}},
+            {"name": {"first": "bob"}, "tags": []}
+        ],
+        "count": 2
+    }"#;
+
+    #[test]
+    fn counting() {
+        let mut counter = CountingHandler::default();
+        parse_with_handler(SRC, &mut counter).unwrap();
+        assert_eq!(counter.nodes, 14);
+
+        // Trailing input is an error, after the handler saw the whole array.
+        let mut counter = CountingHandler::default();
+        let error = parse_with_handler("[1, 2] 3", &mut counter).unwrap_err();
+        assert_eq!(
+            (error.message.as_str(), error.byte_offset),
+            ("unexpected trailing characters", 7)
+        );
+        assert_eq!(counter.nodes, 3);
+    }
+
+    #[test]
+    fn selecting() {
+        let mut names = SelectingHandler::new(|path| path.ends_with("/name"));
+        parse_with_handler(SRC, &mut names).unwrap();
+        assert_eq!(
+            names.into_selected(),
+            [
+                ("/users/0/name".to_owned(), OwnedValue::String("ada".into())),
+                (
+                    "/users/0/meta/name".to_owned(),
+                    OwnedValue::String("inner".into())
+                ),
+                (
+                    "/users/1/name".to_owned(),
+                    parse_complete(r#"{"first": "bob"}"#).unwrap().into_owned()
+                ),
+            ]
+        );
+
+        let mut users = SelectingHandler::new(|path| path.starts_with("/users/"));
+        parse_with_handler(SRC, &mut users).unwrap();
+        let selected = users.into_selected();
+        let expected = parse_complete(SRC).unwrap();
+        assert_eq!(selected.len(), 2);
+        for (path, value) in selected {
+            assert_eq!(value.borrow(), *expected.pointer(&path).unwrap(), "{path}");
+        }
+
+        let mut everything = SelectingHandler::new(|path| path.is_empty());
+        parse_with_handler(SRC, &mut everything).unwrap();
+        assert_eq!(
+            everything.selected(),
+            [(String::new(), parse_complete(SRC).unwrap().into_owned())]
+        );
+    }
+}
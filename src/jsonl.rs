@@ -0,0 +1,194 @@
+use crate::{strip_bom, Context, OwnedValue, ParseConfig, ParseError, Value};
+
+/// Parses [JSON Lines](https://jsonlines.org) input, one value per line.
+///
+/// Blank lines are skipped. A line that is not exactly one JSON value yields
+/// an `Err` and iteration carries on with the next line, so callers decide
+/// whether to skip or abort. Error positions refer to the whole of `src`.
+///
+/// The whole input has to be in memory. To parse input that arrives in
+/// chunks, such as from a socket, use [`JsonlChunks`].
+pub fn parse_jsonl(src: &str) -> ParseJsonl<'_> {
+    ParseJsonl::new(src)
+}
+
+/// Parses newline-delimited JSON (NDJSON), the same format as
+/// [`parse_jsonl`] under another name.
+pub fn parse_lines(src: &str) -> ParseJsonl<'_> {
+    ParseJsonl::new(src)
+}
+
+/// Iterator over the values of a JSON Lines document, see [`parse_jsonl`].
+#[derive(Debug, Clone)]
+pub struct ParseJsonl<'a> {
+    src: &'a str,
+    /// Byte offset of the first line not yet parsed.
+    pos: usize,
+}
+
+impl<'a> ParseJsonl<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            pos: src.len() - strip_bom(src).len(),
+        }
+    }
+}
+
+impl<'a> Iterator for ParseJsonl<'a> {
+    type Item = Result<Value<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.src.len() {
+            let rest = &self.src[self.pos..];
+            let line = match rest.find('\n') {
+                Some(end) => &rest[..end],
+                None => rest,
+            };
+            self.pos += line.len() + 1;
+
+            if !line.trim().is_empty() {
+                return Some(Context::new(self.src, &ParseConfig::default()).parse_complete(line));
+            }
+        }
+
+        None
+    }
+}
+
+/// Parses JSON Lines input that arrives in chunks, keeping the start of a
+/// line that a chunk cuts off until the rest of it is fed.
+///
+/// Accepts the same input as [`parse_jsonl`], with values returned as
+/// [`OwnedValue`]s since the chunks don't outlive the call. Error positions
+/// refer to all the input fed so far, as if it had been one string.
+#[derive(Debug, Clone, Default)]
+pub struct JsonlChunks {
+    /// The start of a line whose end hasn't been fed yet.
+    partial: String,
+    /// Byte offset of `partial` in the whole input.
+    offset: usize,
+    /// The number of lines before `partial`.
+    lines: usize,
+}
+
+impl JsonlChunks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses every line that `chunk` completes, returning a result for
+    /// each one that isn't blank.
+    pub fn feed(&mut self, chunk: &str) -> Vec<Result<OwnedValue, ParseError>> {
+        self.partial.push_str(chunk);
+
+        let mut values = Vec::new();
+        let mut start = 0;
+        while let Some(end) = self.partial[start..].find('\n') {
+            values.extend(self.parse_line(&self.partial[start..start + end], start));
+            start += end + 1;
+            self.lines += 1;
+        }
+
+        self.partial.drain(..start);
+        self.offset += start;
+        values
+    }
+
+    /// Parses the last line, which needs no `\n` after it. Returns `None`
+    /// if it is blank.
+    pub fn finish(self) -> Option<Result<OwnedValue, ParseError>> {
+        self.parse_line(&self.partial, 0)
+    }
+
+    /// Parses `line`, which starts `start` bytes into `self.partial`.
+    fn parse_line(&self, line: &str, start: usize) -> Option<Result<OwnedValue, ParseError>> {
+        let offset = self.offset + start;
+        let src = if offset == 0 { strip_bom(line) } else { line };
+        if src.trim().is_empty() {
+            return None;
+        }
+
+        let parsed = Context::new(line, &ParseConfig::default()).parse_complete(src);
+        Some(parsed.map(Value::into_owned).map_err(|mut error| {
+            error.byte_offset += offset;
+            error.line += self.lines;
+            error
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_per_line() {
+        let src = "{\"a\": 1}\n\n  [true]\r\n\"x\"";
+        let values: Vec<_> = parse_jsonl(src).collect();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0].as_ref().unwrap()["a"], Value::Integer(1));
+        assert_eq!(values[1], Ok(Value::Array(vec![Value::Bool(true)])));
+        assert_eq!(values[2], Ok(Value::String("x".into())));
+    }
+
+    #[test]
+    fn errors_do_not_stop_iteration() {
+        let src = "1\n[2,\n3 4\n5\n";
+        let values: Vec<_> = parse_jsonl(src).collect();
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[0], Ok(Value::Integer(1)));
+
+        let error = values[1].as_ref().unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        let error = values[2].as_ref().unwrap_err();
+        assert_eq!(error.message, "unexpected trailing characters");
+        assert_eq!((error.byte_offset, error.line), (8, 3));
+
+        assert_eq!(values[3], Ok(Value::Integer(5)));
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        let values: Vec<_> = parse_jsonl("\u{feff}1\n2\n").collect();
+        assert_eq!(values, [Ok(Value::Integer(1)), Ok(Value::Integer(2))]);
+    }
+
+    #[test]
+    fn chunks() {
+        let src = "\u{feff}{\"a\": 1}\n\n  [true]\r\n1 2\n\"\u{e9}\" oops\n\"x\"";
+        let expected: Vec<_> = parse_jsonl(src)
+            .map(|value| value.map(Value::into_owned))
+            .collect();
+        assert_eq!(expected.iter().filter(|value| value.is_err()).count(), 2);
+
+        // Split at every character boundary, and into single characters.
+        for split in (0..=src.len()).filter(|&i| src.is_char_boundary(i)) {
+            let mut chunks = JsonlChunks::new();
+            let mut values = chunks.feed(&src[..split]);
+            values.extend(chunks.feed(&src[split..]));
+            values.extend(chunks.finish());
+            assert_eq!(values, expected, "split at {split}");
+        }
+
+        let mut chunks = JsonlChunks::new();
+        let mut values: Vec<_> = src
+            .chars()
+            .flat_map(|c| chunks.feed(c.encode_utf8(&mut [0; 4])))
+            .collect();
+        values.extend(chunks.finish());
+        assert_eq!(values, expected);
+
+        assert_eq!(JsonlChunks::new().finish(), None);
+    }
+
+    #[test]
+    fn parse_lines_is_parse_jsonl() {
+        let src = "{\"level\": \"info\"}\n\n{\"level\": oops}\n[]\n";
+        assert_eq!(
+            parse_lines(src).collect::<Vec<_>>(),
+            parse_jsonl(src).collect::<Vec<_>>()
+        );
+        assert_eq!(parse_lines(src).filter(Result::is_ok).count(), 2);
+    }
+}
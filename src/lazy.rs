@@ -0,0 +1,205 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::{strip_bom, Context, Map, ParseConfig, ParseError, Value};
+
+/// Parses `src` as a single JSON object, leaving the value of each entry
+/// unparsed until it is asked for, see [`LazyValue`].
+///
+/// This is cheaper than [`parse_complete`](crate::parse_complete) when only a
+/// few fields of a large object are needed. The entries are only checked
+/// far enough to find where each value ends, so an invalid value is reported
+/// by [`LazyValue::parse`] rather than here. When a key is repeated, the
+/// last value wins.
+pub fn parse_lazy(src: &str) -> Result<Map<Cow<'_, str>, LazyValue<'_>>, ParseError> {
+    let config = ParseConfig::default();
+    let cx = Context::new(src, &config);
+    let start = cx.skip_whitespace_and_comments(strip_bom(src))?;
+    let Some(rest) = start.strip_prefix('{') else {
+        return Err(cx.error(start, "expected '{'"));
+    };
+    let mut remaining = cx.skip_whitespace_and_comments(rest)?;
+    let mut map = Map::new();
+
+    if let Some(rest) = remaining.strip_prefix('}') {
+        remaining = rest;
+    } else {
+        loop {
+            let (key, _, value) = cx.parse_key(remaining)?;
+            let rest = skip_value(&cx, value)?;
+            let raw = &value[..value.len() - rest.len()];
+            map.insert(key, LazyValue { src, raw });
+
+            remaining = cx.skip_whitespace_and_comments(rest)?;
+            if let Some(rest) = remaining.strip_prefix(',') {
+                remaining = cx.skip_whitespace_and_comments(rest)?;
+            } else if let Some(rest) = remaining.strip_prefix('}') {
+                remaining = rest;
+                break;
+            } else {
+                return Err(cx.error(remaining, "expected ',' or '}' after object value"));
+            }
+        }
+    }
+
+    let remaining = cx.skip_whitespace_and_comments(remaining)?;
+    if !remaining.is_empty() {
+        return Err(cx.error(remaining, "unexpected trailing characters"));
+    }
+    Ok(map)
+}
+
+/// A JSON value that hasn't been parsed yet, see [`parse_lazy`].
+///
+/// Holds the value's source text, and parses it each time
+/// [`LazyValue::parse`] is called. The `is_` methods only look at the first
+/// character, so they don't tell whether the value is valid.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct LazyValue<'a> {
+    /// The whole input, for error positions.
+    src: &'a str,
+    raw: &'a str,
+}
+
+impl<'a> LazyValue<'a> {
+    /// Parses the value. Error positions refer to the whole input given to
+    /// [`parse_lazy`].
+    pub fn parse(&self) -> Result<Value<'a>, ParseError> {
+        Context::new(self.src, &ParseConfig::default()).parse_complete(self.raw)
+    }
+
+    /// Returns the source text of the value, without surrounding whitespace.
+    pub fn raw(&self) -> &'a str {
+        self.raw
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.raw.starts_with('n')
+    }
+
+    pub fn is_bool(&self) -> bool {
+        self.raw.starts_with(['t', 'f'])
+    }
+
+    pub fn is_number(&self) -> bool {
+        self.raw
+            .starts_with(|c: char| c == '-' || c.is_ascii_digit())
+    }
+
+    pub fn is_string(&self) -> bool {
+        self.raw.starts_with('"')
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.raw.starts_with('[')
+    }
+
+    pub fn is_object(&self) -> bool {
+        self.raw.starts_with('{')
+    }
+}
+
+impl fmt::Debug for LazyValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LazyValue").field(&self.raw).finish()
+    }
+}
+
+/// Returns the input after the value at the start of `src`.
+///
+/// Scalars are parsed as usual. Arrays and objects are only scanned for
+/// their closing bracket, skipping over strings.
+fn skip_value<'a>(cx: &Context<'a, '_>, src: &'a str) -> Result<&'a str, ParseError> {
+    if !src.starts_with(['[', '{']) {
+        return cx.parse_scalar(src).map(|(_, rest)| rest);
+    }
+
+    let bytes = src.as_bytes();
+    let mut depth = 0usize;
+    let mut pos = 0;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'"' => {
+                pos += 1;
+                while pos < bytes.len() && bytes[pos] != b'"' {
+                    if bytes[pos] == b'\\' {
+                        pos += 1;
+                    }
+                    pos += 1;
+                }
+            }
+            b'[' | b'{' => depth += 1,
+            b']' | b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(&src[pos + 1..]);
+                }
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+    Err(cx.error(&src[src.len()..], "unexpected end of input"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_complete;
+
+    #[test]
+    fn lazy_entries() {
+        let src = r#"{"id": 7, "tags": ["a", "]"], "meta": {"k": "\"}"}, "none": null, "s": "x"}"#;
+        let map = parse_lazy(src).unwrap();
+        assert_eq!(map.len(), 5);
+
+        assert_eq!(map.get("id").unwrap().raw(), "7");
+        assert_eq!(map.get("tags").unwrap().raw(), r#"["a", "]"]"#);
+        assert_eq!(map.get("meta").unwrap().raw(), r#"{"k": "\"}"}"#);
+        let full = parse_complete(src).unwrap();
+        for (key, lazy) in &map {
+            assert_eq!(lazy.parse().unwrap(), full[key.as_ref()], "{key}");
+        }
+
+        assert!(map.get("id").unwrap().is_number());
+        assert!(map.get("tags").unwrap().is_array());
+        assert!(map.get("meta").unwrap().is_object());
+        assert!(map.get("none").unwrap().is_null());
+        assert!(map.get("s").unwrap().is_string());
+        assert!(!map.get("s").unwrap().is_bool());
+
+        assert!(parse_lazy(" {} ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        for (src, message, offset) in [
+            ("[]", "expected '{'", 0),
+            (
+                "{\"a\": 1 \"b\": 2}",
+                "expected ',' or '}' after object value",
+                8,
+            ),
+            ("{\"a\": [1, 2", "unexpected end of input", 11),
+            ("{\"a\": [\"]", "unexpected end of input", 9),
+            ("{\"a\": tru}", "expected 'true' or 'false'", 6),
+            ("{} x", "unexpected trailing characters", 3),
+        ] {
+            let error = parse_lazy(src).unwrap_err();
+            assert_eq!(
+                (error.message.as_str(), error.byte_offset),
+                (message, offset),
+                "{src}"
+            );
+        }
+
+        // Invalid arrays and objects are only found when parsed.
+        let map = parse_lazy(r#"{"a": [1, x], "b": 2}"#).unwrap();
+        let error = map.get("a").unwrap().parse().unwrap_err();
+        assert_eq!(
+            (error.message.as_str(), error.byte_offset),
+            ("unexpected character 'x'", 10)
+        );
+        assert_eq!(map.get("b").unwrap().parse(), Ok(Value::Integer(2)));
+    }
+}
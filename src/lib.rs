@@ -1,338 +1,680 @@
-use std::collections::HashMap;
-
-#[derive(Debug, PartialEq, Clone)]
-pub enum Value<'a> {
-    Null,
-    Bool(bool),
-    Number(f64),
-    String(&'a str),
-    Array(Vec<Value<'a>>),
-    Object(HashMap<&'a str, Value<'a>>),
+use std::borrow::Cow;
+
+mod concat;
+mod config;
+mod eq;
+mod error;
+mod events;
+mod handler;
+mod jsonl;
+mod lazy;
+mod macros;
+pub mod map;
+mod owned;
+mod parser;
+mod patch;
+mod pointer;
+mod query;
+mod reader;
+mod ser;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod spans;
+mod stream;
+mod tokenizer;
+mod value;
+mod visit;
+mod writer;
+
+pub use concat::{parse_all, ParseAll};
+pub use config::{DuplicateKeyBehavior, ParseConfig, ParseOptions, WriteConfig};
+pub use error::{position_of, ErrorKind, ParseError};
+pub use events::{parse_events, Event};
+pub use handler::{parse_with_handler, CountingHandler, JsonHandler, SelectingHandler};
+pub use jsonl::{parse_jsonl, parse_lines, JsonlChunks, ParseJsonl};
+pub use lazy::{parse_lazy, LazyValue};
+pub use map::Map;
+pub use owned::OwnedValue;
+pub use parser::Parser;
+pub use patch::{Difference, PatchError, PatchOp};
+pub use reader::{parse_reader, parse_reader_limited};
+pub use ser::Pretty;
+pub use spans::{parse_spanned, Spans};
+pub use stream::{parse_array_stream, parse_array_stream_with_config, ArrayStream};
+pub use tokenizer::{Token, Tokenizer};
+pub use value::{KeyPath, TryFromValueError, Value};
+pub use visit::{traverse, Visitor};
+pub use writer::JsonWriter;
+
+/// Parses the first JSON value in `src`.
+///
+/// A leading byte order mark is skipped, here and in the other parsing
+/// functions.
+///
+/// Returns `Ok(None)` when `src` contains nothing but whitespace. Any input
+/// following the first value is ignored. When an object repeats a key, the
+/// last value wins.
+pub fn parse(src: &str) -> Result<Option<Value<'_>>, ParseError> {
+    parse_with_config(src, &ParseConfig::default())
 }
 
-pub fn parse(src: &str) -> ElementParseOption<Option<Value>> {
-    let src = src.trim();
+/// Like [`parse`], with `config` controlling what is accepted.
+pub fn parse_with_config<'a>(
+    src: &'a str,
+    config: &ParseConfig,
+) -> Result<Option<Value<'a>>, ParseError> {
+    let parsed = Context::new(src, config).parse_one(strip_bom(src))?;
+    Ok(parsed.map(|(value, _)| value))
+}
 
-    if src.is_empty() {
-        return Some((None, None));
-    }
+/// Same as [`parse_with_config`].
+pub fn parse_with<'a>(
+    src: &'a str,
+    options: &ParseOptions,
+) -> Result<Option<Value<'a>>, ParseError> {
+    parse_with_config(src, options)
+}
 
-    if let Some(((), remaining)) = parse_null(src) {
-        return Some((Some(Value::Null), remaining));
-    }
+/// Parses `src` as a single JSON document.
+///
+/// Unlike [`parse`], anything other than whitespace after the value is an
+/// error, and so is an input without any value at all.
+pub fn parse_complete(src: &str) -> Result<Value<'_>, ParseError> {
+    Context::new(src, &ParseConfig::default()).parse_complete(strip_bom(src))
+}
 
-    if let Some((value, remaining)) = parse_bool(src) {
-        return Some((Some(Value::Bool(value)), remaining));
+/// Like [`parse`], for input that may be borrowed or owned.
+///
+/// Borrowed input is parsed in place. Owned input is dropped after parsing,
+/// so the result copies its strings out of it, see [`Value::into_static`].
+pub fn parse_cow(src: Cow<'_, str>) -> Result<Option<Value<'_>>, ParseError> {
+    match src {
+        Cow::Borrowed(src) => parse(src),
+        Cow::Owned(src) => Ok(parse(&src)?.map(Value::into_static)),
     }
+}
 
-    if let Some((value, remaining)) = parse_number(src) {
-        return Some((Some(Value::Number(value)), remaining));
-    }
+/// Same as [`parse_complete`].
+pub fn parse_strict(src: &str) -> Result<Value<'_>, ParseError> {
+    parse_complete(src)
+}
 
-    if let Some((value, remaining)) = parse_string(src) {
-        return Some((Some(Value::String(value)), remaining));
-    }
+/// Like [`parse`], for input that has not been checked to be UTF-8 yet.
+///
+/// Input that is not valid UTF-8 fails with an error at the first invalid
+/// byte. Valid input is parsed in place without copying.
+pub fn parse_bytes(src: &[u8]) -> Result<Option<Value<'_>>, ParseError> {
+    parse(str_from_utf8(src)?)
+}
+
+/// Skips the UTF-8 byte order mark some editors write at the start of a
+/// file. Error offsets still count it, as they refer to the whole input.
+fn strip_bom(src: &str) -> &str {
+    src.strip_prefix('\u{feff}').unwrap_or(src)
+}
+
+fn str_from_utf8(src: &[u8]) -> Result<&str, ParseError> {
+    std::str::from_utf8(src).map_err(|e| {
+        // The prefix before the first invalid byte is valid UTF-8.
+        let valid = std::str::from_utf8(&src[..e.valid_up_to()]).unwrap_or_default();
+        ParseError::new("invalid UTF-8", valid, valid.len())
+    })
+}
+
+/// A parsed element together with the input that follows it.
+type ElementParseResult<'a, T> = Result<(T, &'a str), ParseError>;
+
+/// An array or object whose elements are still being parsed.
+enum Frame<'a> {
+    Array(Vec<Value<'a>>),
+    Object {
+        map: Map<Cow<'a, str>, Value<'a>>,
+        /// The key whose value is being parsed.
+        key: Cow<'a, str>,
+        /// Where `key` starts, for duplicate key errors.
+        key_start: &'a str,
+    },
+}
+
+/// State shared by the element parsers for a single call to [`parse`].
+struct Context<'a, 'c> {
+    /// The complete input, used to turn the remaining input at the point of
+    /// failure into a byte offset.
+    src: &'a str,
+    config: &'c ParseConfig,
+}
 
-    if let Some((value, remaining)) = parse_array(src) {
-        return Some((Some(Value::Array(value)), remaining));
+impl<'a, 'c> Context<'a, 'c> {
+    fn new(src: &'a str, config: &'c ParseConfig) -> Self {
+        Self { src, config }
     }
-    if let Some((value, remaining)) = parse_object(src) {
-        return Some((Some(Value::Object(value)), remaining));
+
+    /// Creates an error at the start of `remaining`, which must be a slice of
+    /// `self.src`.
+    fn error(&self, remaining: &str, message: impl Into<String>) -> ParseError {
+        ParseError::new(message, self.src, self.offset(remaining))
     }
 
-    None
-}
+    /// Returns the byte offset of `remaining`, a slice of `self.src`.
+    fn offset(&self, remaining: &str) -> usize {
+        remaining.as_ptr() as usize - self.src.as_ptr() as usize
+    }
 
-impl std::fmt::Display for Value<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Null => write!(f, "null"),
-            Self::Bool(b) => write!(f, "{b}"),
-            Self::String(str) => write!(f, "{str}"),
-            Self::Number(num) => write!(f, "{num}"),
-            Self::Array(arr) => {
-                writeln!(f, "[")?;
-                for e in arr {
-                    writeln!(f, "  {e}")?;
-                }
-                writeln!(f, "]")
-            }
-            Self::Object(values) => {
-                writeln!(f, "{{")?;
-                for (k, v) in values.iter() {
-                    writeln!(f, "{k}: {v}")?;
-                }
+    /// Parses the value at the start of `src`, a slice of `self.src`, after
+    /// any whitespace. Returns `None` if there is nothing but whitespace.
+    fn parse_one(&self, src: &'a str) -> Result<Option<(Value<'a>, &'a str)>, ParseError> {
+        self.check_input_size()?;
+        let src = self.skip_whitespace_and_comments(src)?;
 
-                writeln!(f, "}}")
-            }
+        if src.is_empty() {
+            return Ok(None);
         }
+
+        self.parse_value(src).map(Some)
     }
-}
 
-type ElementParseOption<'a, T> = Option<(T, Option<&'a str>)>;
+    /// Parses `src`, a slice of `self.src`, as exactly one value surrounded
+    /// by optional whitespace.
+    fn parse_complete(&self, src: &'a str) -> Result<Value<'a>, ParseError> {
+        self.check_input_size()?;
+        let (value, remaining) = self.parse_value(self.skip_whitespace_and_comments(src)?)?;
+        let remaining = self.skip_whitespace_and_comments(remaining)?;
 
-fn parse_array(src: &str) -> ElementParseOption<Vec<Value<'_>>> {
-    let mut remaining = src.trim_start();
+        if !remaining.is_empty() {
+            return Err(self.error(remaining, "unexpected trailing characters"));
+        }
 
-    if !remaining.starts_with('[') {
-        return None;
+        Ok(value)
     }
 
-    remaining = remaining[1..].trim_start();
+    /// Parses any value.
+    ///
+    /// Arrays and objects that are still open are kept on a heap-allocated
+    /// stack rather than the call stack, so deep nesting is only limited by
+    /// `max_depth`.
+    fn parse_value(&self, src: &'a str) -> ElementParseResult<'a, Value<'a>> {
+        self.parse_nested_value(src, 0)
+    }
 
-    let mut elements = Vec::new();
+    /// Like [`Context::parse_value`], for a value inside `depth` arrays and
+    /// objects that count towards `max_depth`.
+    fn parse_nested_value(&self, src: &'a str, depth: usize) -> ElementParseResult<'a, Value<'a>> {
+        let mut stack: Vec<Frame<'a>> = Vec::new();
+        let mut remaining = src;
 
-    loop {
-        if remaining.starts_with(']') {
-            remaining = remaining[1..].trim_start();
-            return Some((
-                elements,
-                if remaining.is_empty() {
-                    None
-                } else {
-                    Some(remaining)
-                },
-            ));
-        }
+        loop {
+            let mut value = match remaining.as_bytes().first() {
+                Some(b'[') => {
+                    self.check_depth(remaining, depth + stack.len() + 1)?;
+                    remaining = self.skip_whitespace_and_comments(&remaining[1..])?;
 
-        let (element, next_remaining) = match parse(remaining) {
-            Some((Some(e), r)) => (e, r),
-            _ => return None,
-        };
+                    match remaining.strip_prefix(']') {
+                        Some(rest) => {
+                            remaining = rest;
+                            Value::Array(Vec::new())
+                        }
+                        None => {
+                            stack.push(Frame::Array(Vec::new()));
+                            continue;
+                        }
+                    }
+                }
+                Some(b'{') => {
+                    self.check_depth(remaining, depth + stack.len() + 1)?;
+                    remaining = self.skip_whitespace_and_comments(&remaining[1..])?;
 
-        elements.push(element);
+                    match remaining.strip_prefix('}') {
+                        Some(rest) => {
+                            remaining = rest;
+                            Value::Object(Map::new())
+                        }
+                        None => {
+                            let (key, key_start, rest) = self.parse_key(remaining)?;
+                            remaining = rest;
+                            stack.push(Frame::Object {
+                                map: Map::new(),
+                                key,
+                                key_start,
+                            });
+                            continue;
+                        }
+                    }
+                }
+                _ => {
+                    let (value, rest) = self.parse_scalar(remaining)?;
+                    remaining = rest;
+                    value
+                }
+            };
 
-        remaining = match next_remaining {
-            Some(r) => r.trim_start(),
-            None => "",
-        };
+            // Hand the finished value to the innermost open container, closing
+            // containers for as long as that completes them.
+            loop {
+                let Some(frame) = stack.last_mut() else {
+                    return Ok((value, remaining));
+                };
 
-        if remaining.starts_with(',') {
-            remaining = remaining[1..].trim_start();
-        } else if remaining.starts_with(']') {
-            continue;
-        } else {
-            return None;
+                remaining = self.skip_whitespace_and_comments(remaining)?;
+
+                match frame {
+                    Frame::Array(elements) => {
+                        elements.push(value);
+
+                        if let Some(rest) = remaining.strip_prefix(',') {
+                            remaining = self.skip_whitespace_and_comments(rest)?;
+                            match self.strip_trailing_close(remaining, ']') {
+                                Some(rest) => remaining = rest,
+                                None => break,
+                            }
+                        } else if let Some(rest) = remaining.strip_prefix(']') {
+                            remaining = rest;
+                        } else {
+                            return Err(
+                                self.error(remaining, "expected ',' or ']' after array element")
+                            );
+                        }
+                    }
+                    Frame::Object {
+                        map,
+                        key,
+                        key_start,
+                    } => {
+                        let name = std::mem::take(key);
+
+                        if !map.contains_key(&name) {
+                            map.insert(name, value);
+                        } else {
+                            match self.config.duplicate_key_behavior {
+                                DuplicateKeyBehavior::UseLast => {
+                                    map.insert(name, value);
+                                }
+                                DuplicateKeyBehavior::UseFirst => {}
+                                DuplicateKeyBehavior::Error => {
+                                    return Err(
+                                        self.error(key_start, format!("duplicate key {name:?}"))
+                                    )
+                                }
+                            }
+                        }
+
+                        if let Some(rest) = remaining.strip_prefix(',') {
+                            let rest = self.skip_whitespace_and_comments(rest)?;
+                            if let Some(rest) = self.strip_trailing_close(rest, '}') {
+                                remaining = rest;
+                            } else {
+                                let (next_key, next_key_start, rest) = self.parse_key(rest)?;
+                                *key = next_key;
+                                *key_start = next_key_start;
+                                remaining = rest;
+                                break;
+                            }
+                        } else if let Some(rest) = remaining.strip_prefix('}') {
+                            remaining = rest;
+                        } else {
+                            return Err(
+                                self.error(remaining, "expected ',' or '}' after object value")
+                            );
+                        }
+                    }
+                }
+
+                value = match stack.pop() {
+                    Some(Frame::Array(elements)) => Value::Array(elements),
+                    Some(Frame::Object { map, .. }) => Value::Object(map),
+                    None => unreachable!("a frame was just inspected"),
+                };
+            }
         }
     }
-}
 
-fn parse_object(src: &str) -> ElementParseOption<HashMap<&'_ str, Value<'_>>> {
-    let mut remaining = src.trim_start();
+    /// Parses a value that is neither an array nor an object.
+    fn parse_scalar(&self, src: &'a str) -> ElementParseResult<'a, Value<'a>> {
+        if self.config.allow_non_finite {
+            if let Some(parsed) = self.parse_non_finite(src)? {
+                return Ok(parsed);
+            }
+        }
 
-    if !remaining.starts_with('{') {
-        return None;
+        match src.as_bytes().first() {
+            Some(b'n') => self.parse_null(src).map(|((), r)| (Value::Null, r)),
+            Some(b't' | b'f') => self.parse_bool(src).map(|(b, r)| (Value::Bool(b), r)),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(src),
+            Some(b'"') => self.parse_string(src).map(|(s, r)| (Value::String(s), r)),
+            Some(_) => {
+                let c = src.chars().next().unwrap_or_default();
+                Err(self.error(src, format!("unexpected character {c:?}")))
+            }
+            None => Err(self.error(src, "unexpected end of input")),
+        }
     }
 
-    remaining = remaining[1..].trim_start();
+    /// Skips whitespace, and `//` line comments and `/* */` block comments
+    /// if they are allowed.
+    fn skip_whitespace_and_comments(&self, src: &'a str) -> Result<&'a str, ParseError> {
+        let mut remaining = src.trim_start();
 
-    let mut map = HashMap::new();
+        if !self.config.allow_comments {
+            return Ok(remaining);
+        }
 
-    loop {
-        if remaining.starts_with('}') {
-            remaining = remaining[1..].trim_start();
-            return Some((
-                map,
-                if remaining.is_empty() {
-                    None
-                } else {
-                    Some(remaining)
-                },
-            ));
+        loop {
+            if let Some(rest) = remaining.strip_prefix("//") {
+                let end = rest.find(['\n', '\r']).unwrap_or(rest.len());
+                remaining = rest[end..].trim_start();
+            } else if let Some(rest) = remaining.strip_prefix("/*") {
+                match rest.find("*/") {
+                    Some(end) => remaining = rest[end + 2..].trim_start(),
+                    None => return Err(self.error(remaining, "unterminated block comment")),
+                }
+            } else {
+                return Ok(remaining);
+            }
         }
+    }
 
-        let (key, next_remaining) = match parse_string(remaining) {
-            Some((k, next)) => (k, next),
-            _ => return None,
-        };
+    /// Fails if the whole input is longer than the configured limit.
+    fn check_input_size(&self) -> Result<(), ParseError> {
+        match self.config.max_input_bytes {
+            Some(limit) if self.src.len() > limit => Err(ParseError::input_too_large(limit)),
+            _ => Ok(()),
+        }
+    }
 
-        remaining = match next_remaining {
-            Some(r) => r.trim_start(),
-            None => return None,
-        };
+    /// Fails if an array or object at `depth` would nest too deeply.
+    fn check_depth(&self, src: &'a str, depth: usize) -> Result<(), ParseError> {
+        if depth > self.config.max_depth {
+            return Err(self
+                .error(src, "maximum nesting depth exceeded")
+                .with_kind(ErrorKind::DepthLimitExceeded));
+        }
+        Ok(())
+    }
 
-        if !remaining.starts_with(':') {
+    /// Fails if the string starting at `src` is already `len` bytes long
+    /// and that is over the configured limit.
+    fn check_string_len(&self, src: &'a str, len: usize) -> Result<(), ParseError> {
+        match self.config.max_string_bytes {
+            Some(limit) if len > limit => Err(self
+                .error(src, format!("string is longer than {limit} bytes"))
+                .with_kind(ErrorKind::StringTooLong)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Strips the `close` bracket at the start of `src`, which follows a `,`,
+    /// if trailing commas are allowed.
+    fn strip_trailing_close(&self, src: &'a str, close: char) -> Option<&'a str> {
+        if !self.config.allow_trailing_commas {
             return None;
         }
+        src.strip_prefix(close)
+    }
 
-        remaining = remaining[1..].trim_start();
+    /// Parses an object key and the `:` after it, returning the key, its
+    /// source text including the quotes, and the input after the `:` and
+    /// any whitespace.
+    fn parse_key(&self, src: &'a str) -> Result<(Cow<'a, str>, &'a str, &'a str), ParseError> {
+        if !src.starts_with('"') {
+            return Err(self.error(src, "expected string key"));
+        }
 
-        let (value, next_remaining_value) = match parse(remaining) {
-            Some((Some(v), next)) => (v, next),
-            _ => return None,
-        };
+        let (key, remaining) = self.parse_string(src)?;
+        let raw = &src[..src.len() - remaining.len()];
+        let remaining = self.skip_whitespace_and_comments(remaining)?;
 
-        map.insert(key, value);
+        match remaining.strip_prefix(':') {
+            Some(rest) => Ok((key, raw, self.skip_whitespace_and_comments(rest)?)),
+            None => Err(self.error(remaining, "expected ':' after object key")),
+        }
+    }
 
-        remaining = match next_remaining_value {
-            Some(r) => r.trim_start(),
-            None => "",
-        };
+    fn parse_null(&self, src: &'a str) -> ElementParseResult<'a, ()> {
+        match self.strip_keyword(src, "null")? {
+            Some(rest) => Ok(((), rest)),
+            None => Err(self.error(src, "expected 'null'")),
+        }
+    }
 
-        if remaining.starts_with(',') {
-            remaining = remaining[1..].trim_start();
-        } else if remaining.starts_with('}') {
-            continue;
+    fn parse_bool(&self, src: &'a str) -> ElementParseResult<'a, bool> {
+        if let Some(rest) = self.strip_keyword(src, "true")? {
+            Ok((true, rest))
+        } else if let Some(rest) = self.strip_keyword(src, "false")? {
+            Ok((false, rest))
         } else {
-            return None;
+            Err(self.error(src, "expected 'true' or 'false'"))
         }
     }
-}
 
-fn parse_null(src: &str) -> ElementParseOption<()> {
-    if src.starts_with("null") {
-        Some((
-            (),
-            match src.split_at(4).1 {
-                x if x.is_empty() => None,
-                x => Some(x),
-            },
-        ))
-    } else {
-        None
+    /// Strips `keyword` from the start of `src`, failing if it runs into an
+    /// identifier character, as in `nullable`.
+    fn strip_keyword(&self, src: &'a str, keyword: &str) -> Result<Option<&'a str>, ParseError> {
+        let Some(rest) = src.strip_prefix(keyword) else {
+            return Ok(None);
+        };
+
+        match rest.chars().next() {
+            Some(c) if !self.is_delimiter(c) => Err(self.error(
+                rest,
+                format!("unexpected character {c:?} after '{keyword}'"),
+            )),
+            _ => Ok(Some(rest)),
+        }
     }
-}
 
-fn parse_bool(src: &str) -> ElementParseOption<bool> {
-    match src {
-        _t if src.starts_with("true") => Some((
-            true,
-            match src.split_at(4).1 {
-                x if x.is_empty() => None,
-                x => Some(x),
-            },
-        )),
-        _f if src.starts_with("false") => Some((
-            false,
-            match src.split_at(5).1 {
-                x if x.is_empty() => None,
-                x => Some(x),
-            },
-        )),
-        _ => None,
+    /// Whether `c` may directly follow a keyword: whitespace, `,`, `]`, `}`,
+    /// the start of an array, object or string as in concatenated JSON, or
+    /// the `/` of a comment if comments are allowed.
+    fn is_delimiter(&self, c: char) -> bool {
+        c.is_whitespace()
+            || matches!(c, ',' | ']' | '}' | '[' | '{' | '"')
+            || (c == '/' && self.config.allow_comments)
     }
-}
 
-fn parse_number(src: &str) -> ElementParseOption<f64> {
-    let bytes = src.as_bytes();
-    let mut pos = 0;
-    let _len = bytes.len();
+    /// Parses `NaN`, `Infinity` or `-Infinity` (or `-NaN`), if `src` starts
+    /// with one of them.
+    fn parse_non_finite(&self, src: &'a str) -> Result<Option<(Value<'a>, &'a str)>, ParseError> {
+        let (sign, unsigned) = match src.strip_prefix('-') {
+            Some(rest) => (-1.0, rest),
+            None => (1.0, src),
+        };
 
-    if bytes.get(pos) == Some(&b'-') {
-        pos += 1;
+        for (keyword, n) in [("Infinity", f64::INFINITY), ("NaN", f64::NAN)] {
+            if let Some(rest) = self.strip_keyword(unsigned, keyword)? {
+                return Ok(Some((Value::Number(sign * n), rest)));
+            }
+        }
+        Ok(None)
     }
 
-    match bytes.get(pos) {
-        Some(b'0') => {
+    /// Parses a number as [`Value::Integer`] when it has neither a fraction
+    /// nor an exponent and fits in an `i64`, and as [`Value::Number`]
+    /// otherwise. With the `i128` feature, such numbers that fit in an
+    /// `i128` become [`Value::BigInteger`] instead, and only larger ones are
+    /// a [`Value::Number`].
+    ///
+    /// Numbers too large for an `f64`, like `1e400`, fail unless non-finite
+    /// numbers are allowed, in which case they become infinite. Other
+    /// numbers are rounded to the nearest `f64`, so very long literals lose
+    /// precision. With raw numbers on, every valid literal is kept as
+    /// [`Value::RawNumber`] without being converted at all.
+    fn parse_number(&self, src: &'a str) -> ElementParseResult<'a, Value<'a>> {
+        let bytes = src.as_bytes();
+        let mut pos = 0;
+
+        if bytes.get(pos) == Some(&b'-') {
             pos += 1;
-            if bytes.get(pos).map_or(false, |c| c.is_ascii_digit()) {
-                return None;
-            }
         }
-        Some(c) if c.is_ascii_digit() => {
-            pos += 1;
-            while bytes.get(pos).map_or(false, |c| c.is_ascii_digit()) {
+
+        match bytes.get(pos) {
+            Some(b'0') => {
                 pos += 1;
+                if bytes.get(pos).is_some_and(|c| c.is_ascii_digit()) {
+                    return Err(self.error(&src[pos..], "leading zeros are not allowed"));
+                }
+            }
+            Some(c) if c.is_ascii_digit() => {
+                pos += 1;
+                while bytes.get(pos).is_some_and(|c| c.is_ascii_digit()) {
+                    pos += 1;
+                }
             }
+            _ => return Err(self.error(&src[pos..], "expected digit")),
         }
-        _ => return None,
-    }
 
-    if bytes.get(pos) == Some(&b'.') {
-        pos += 1;
-        let digits_start = pos;
-        while bytes.get(pos).map_or(false, |c| c.is_ascii_digit()) {
+        let integer_end = pos;
+
+        if bytes.get(pos) == Some(&b'.') {
             pos += 1;
+            let digits_start = pos;
+            while bytes.get(pos).is_some_and(|c| c.is_ascii_digit()) {
+                pos += 1;
+            }
+            if pos == digits_start {
+                return Err(self.error(&src[pos..], "expected digit after decimal point"));
+            }
         }
-        if pos == digits_start {
-            return None;
-        }
-    }
 
-    if bytes
-        .get(pos)
-        .filter(|c| **c == b'e' || **c == b'E')
-        .is_some()
-    {
-        pos += 1;
         if bytes
             .get(pos)
-            .filter(|c| **c == b'+' || **c == b'-')
+            .filter(|c| **c == b'e' || **c == b'E')
             .is_some()
         {
             pos += 1;
+            if bytes
+                .get(pos)
+                .filter(|c| **c == b'+' || **c == b'-')
+                .is_some()
+            {
+                pos += 1;
+            }
+            let digits_start = pos;
+            while bytes.get(pos).is_some_and(|c| c.is_ascii_digit()) {
+                pos += 1;
+            }
+            if pos == digits_start {
+                return Err(self.error(&src[pos..], "expected digit in exponent"));
+            }
         }
-        let digits_start = pos;
-        while bytes.get(pos).map_or(false, |c| c.is_ascii_digit()) {
-            pos += 1;
-        }
-        if pos == digits_start {
-            return None;
-        }
-    }
 
-    (!src.is_empty() && pos > 0)
-        .then(|| src[..pos].parse().ok())
-        .flatten()
-        .map(|n| {
-            (
-                n,
-                match &src[pos..] {
-                    x if x.is_empty() => None,
-                    x => Some(x),
-                },
-            )
-        })
-}
+        let (literal, rest) = src.split_at(pos);
+        if self.config.raw_numbers {
+            return Ok((Value::RawNumber(Cow::Borrowed(literal)), rest));
+        }
 
-fn parse_string<'a>(src: &'a str) -> ElementParseOption<'a, &'a str> {
-    if !src.starts_with('"') {
-        return None;
+        let integer = (pos == integer_end)
+            .then(|| value::parse_integer(literal))
+            .flatten();
+        let number = match integer {
+            Some(integer) => integer,
+            None => match literal.parse::<f64>() {
+                Ok(n) if n.is_infinite() && !self.config.allow_non_finite => {
+                    return Err(self.error(src, "number out of range"));
+                }
+                Ok(n) => Value::Number(n),
+                Err(_) => return Err(self.error(src, "invalid number")),
+            },
+        };
+        Ok((number, rest))
     }
 
-    let bytes = src.as_bytes();
-    let mut pos = 1;
-    let mut escaped = false;
+    /// Parses a string literal, borrowing from `src` unless the literal
+    /// contains escape sequences that have to be decoded.
+    fn parse_string(&self, src: &'a str) -> ElementParseResult<'a, Cow<'a, str>> {
+        let bytes = src.as_bytes();
+        let mut pos = 1;
+        // Start of the input not yet copied into `decoded`.
+        let mut copied = 1;
+        let mut decoded: Option<String> = None;
 
-    while pos < bytes.len() {
-        if escaped {
+        while pos < bytes.len() {
             match bytes[pos] {
-                b'u' => {
-                    if pos + 4 >= bytes.len() {
-                        return None;
-                    }
-                    pos += 4;
-                }
-                _ => {
-                    pos += 1;
-                }
-            }
-            escaped = false;
-        } else {
-            match bytes[pos] {
-                b'\\' => {
-                    escaped = true;
-                    pos += 1;
-                }
                 b'"' => {
-                    let string_slice = &src[1..pos];
-                    let remaining = if pos + 1 > src.len() {
-                        None
-                    } else {
-                        match &src[pos + 1..] {
-                            x if x.is_empty() => None,
-                            x => Some(x),
+                    let string = match decoded {
+                        Some(mut decoded) => {
+                            decoded.push_str(&src[copied..pos]);
+                            Cow::Owned(decoded)
                         }
+                        None => Cow::Borrowed(&src[1..pos]),
                     };
-                    return Some((string_slice, remaining));
+                    self.check_string_len(src, string.len())?;
+                    return Ok((string, &src[pos + 1..]));
                 }
-                c if c < 0x20 => return None,
+                b'\\' => {
+                    let decoded = decoded.get_or_insert_with(String::new);
+                    decoded.push_str(&src[copied..pos]);
+
+                    let (unescaped, rest) = self.parse_escape(&src[pos..])?;
+                    decoded.push(unescaped);
+                    self.check_string_len(src, decoded.len())?;
+                    pos = src.len() - rest.len();
+                    copied = pos;
+                }
+                c if c < 0x20 => return Err(self.error(&src[pos..], "control character in string")),
                 _ => pos += 1,
             }
         }
+
+        Err(self.error(&src[pos..], "unterminated string"))
     }
 
-    None
+    /// Decodes the escape sequence at the start of `src`, which begins with
+    /// the backslash.
+    fn parse_escape(&self, src: &'a str) -> ElementParseResult<'a, char> {
+        let unescaped = match src.as_bytes().get(1) {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{08}',
+            Some(b'f') => '\u{0c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                let (mut code, mut rest) = self.parse_hex4(&src[2..])?;
+
+                // Characters outside the BMP are written as a high surrogate
+                // followed by a low surrogate.
+                if (0xD800..=0xDBFF).contains(&code) {
+                    let low = match rest.strip_prefix("\\u") {
+                        Some(low) => low,
+                        None => return Err(self.error(src, "unpaired surrogate in unicode escape")),
+                    };
+                    let (low, after_low) = self.parse_hex4(low)?;
+                    if !(0xDC00..=0xDFFF).contains(&low) {
+                        return Err(self.error(src, "unpaired surrogate in unicode escape"));
+                    }
+                    code = (code - 0xD800) * 0x400 + (low - 0xDC00) + 0x10000;
+                    rest = after_low;
+                }
+
+                return match char::from_u32(code) {
+                    Some(c) => Ok((c, rest)),
+                    None => Err(self.error(src, "unpaired surrogate in unicode escape")),
+                };
+            }
+            Some(_) => return Err(self.error(&src[1..], "invalid escape")),
+            None => return Err(self.error(&src[1..], "unterminated string")),
+        };
+
+        Ok((unescaped, &src[2..]))
+    }
+
+    /// Parses the four hex digits of a `\u` escape.
+    fn parse_hex4(&self, src: &'a str) -> ElementParseResult<'a, u32> {
+        let mut code = 0;
+
+        for (i, c) in src.bytes().take(4).enumerate() {
+            match (c as char).to_digit(16) {
+                Some(digit) => code = code * 16 + digit,
+                None => return Err(self.error(&src[i..], "expected hex digit in unicode escape")),
+            }
+        }
+
+        match src.get(4..) {
+            Some(rest) => Ok((code, rest)),
+            None => Err(self.error(&src[src.len()..], "unterminated unicode escape")),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -340,41 +682,637 @@ mod tests {
     use super::*;
     #[test]
     fn parse_nothing() {
-        assert_eq!(parse(""), Some((None, None)))
+        assert_eq!(parse(""), Ok(None))
     }
 
     #[test]
     fn parse_null() {
-        assert_eq!(parse("  null asd"), Some((Some(Value::Null), Some(" asd"))))
+        assert_eq!(parse("  null asd"), Ok(Some(Value::Null)))
     }
 
     #[test]
     fn parse_bool() {
+        assert_eq!(parse("false asd"), Ok(Some(Value::Bool(false))));
+        assert_eq!(parse("true das"), Ok(Some(Value::Bool(true))));
+    }
+
+    #[test]
+    fn keywords_end_at_a_boundary() {
+        assert_eq!(
+            error_at("nullable"),
+            ("unexpected character 'a' after 'null'".into(), 4, Some('a'))
+        );
         assert_eq!(
-            parse("false asd"),
-            Some((Some(Value::Bool(false)), Some(" asd")))
+            error_at("[trueish]"),
+            ("unexpected character 'i' after 'true'".into(), 5, Some('i'))
         );
         assert_eq!(
-            parse("true das"),
-            Some((Some(Value::Bool(true)), Some(" das")))
+            error_at("false_"),
+            (
+                "unexpected character '_' after 'false'".into(),
+                5,
+                Some('_')
+            )
         );
+        assert_eq!(error_at("true1").1, 4);
+        assert_eq!(
+            parse("[true,null]"),
+            Ok(Some(Value::Array(vec![Value::Bool(true), Value::Null])))
+        );
+        for src in ["null-", "nulll", "truefalse", "falsenull", "true/"] {
+            assert!(parse(src).is_err(), "{src}");
+        }
+        assert_eq!(parse("null[]"), Ok(Some(Value::Null)));
+        assert_eq!(parse("true\"x\""), Ok(Some(Value::Bool(true))));
+        assert_eq!(parse("null\n"), Ok(Some(Value::Null)));
+        assert_eq!(
+            parse_complete("{\"a\":true}"),
+            Ok(Value::Object(Map::from([("a".into(), Value::Bool(true))])))
+        );
+
+        let comments = ParseConfig::new().allow_comments(true);
+        assert_eq!(
+            parse_with_config("[false/**/]", &comments),
+            Ok(Some(Value::Array(vec![Value::Bool(false)])))
+        );
+        let non_finite = ParseConfig::new().allow_non_finite(true);
+        assert!(parse_with_config("[NaN-]", &non_finite).is_err());
     }
 
     #[test]
     fn parse_numbers() {
-        assert_eq!(parse("123"), Some((Some(Value::Number(123.0)), None)));
-        assert_eq!(parse("-123"), Some((Some(Value::Number(-123.0)), None)));
-        assert_eq!(parse("0.123"), Some((Some(Value::Number(0.123)), None)));
-        assert_eq!(parse("-0.123"), Some((Some(Value::Number(-0.123)), None)));
-        assert_eq!(parse("1e1"), Some((Some(Value::Number(10.0)), None)));
-        assert_eq!(parse("1e-1"), Some((Some(Value::Number(0.1)), None)));
-        assert_eq!(parse("-1e-1"), Some((Some(Value::Number(-0.1)), None)));
-        assert_eq!(parse("1.1e1"), Some((Some(Value::Number(11.0)), None)));
-        assert_eq!(parse("-1.1e1"), Some((Some(Value::Number(-11.0)), None)));
+        assert_eq!(parse("123"), Ok(Some(Value::Integer(123))));
+        assert_eq!(parse("-123"), Ok(Some(Value::Integer(-123))));
+        assert_eq!(parse("123.0"), Ok(Some(Value::Number(123.0))));
+        assert_eq!(parse("0.123"), Ok(Some(Value::Number(0.123))));
+        assert_eq!(parse("-0.123"), Ok(Some(Value::Number(-0.123))));
+        assert_eq!(parse("1e1"), Ok(Some(Value::Number(10.0))));
+        assert_eq!(parse("1e-1"), Ok(Some(Value::Number(0.1))));
+        assert_eq!(parse("-1e-1"), Ok(Some(Value::Number(-0.1))));
+        assert_eq!(parse("1.1e1"), Ok(Some(Value::Number(11.0))));
+        assert_eq!(parse("-1.1e1"), Ok(Some(Value::Number(-11.0))));
+    }
+
+    #[test]
+    fn parse_integers() {
+        assert_eq!(
+            parse("9007199254740993"),
+            Ok(Some(Value::Integer(9007199254740993)))
+        );
+        assert_eq!(
+            parse("-9223372036854775808"),
+            Ok(Some(Value::Integer(i64::MIN)))
+        );
+        #[cfg(not(feature = "i128"))]
+        assert_eq!(
+            parse("9223372036854775808"),
+            Ok(Some(Value::Number(9223372036854775808.0)))
+        );
+        assert_eq!(parse("1e2"), Ok(Some(Value::Number(100.0))));
+    }
+
+    #[test]
+    fn number_overflow() {
+        let huge = "9".repeat(400);
+        for src in ["1e400", "-1e400", "[1, 1.5e309]", &huge] {
+            let error = parse(src).unwrap_err();
+            assert_eq!(error.message, "number out of range", "{src}");
+        }
+        assert_eq!(parse("[1, 1.5e309]").unwrap_err().byte_offset, 4);
+
+        // Underflow and long literals that fit are rounded instead.
+        assert_eq!(parse("1e-400"), Ok(Some(Value::Number(0.0))));
+        assert_eq!(
+            parse(&"1".repeat(40)),
+            Ok(Some(Value::Number(1.111_111_111_111_111_2e39)))
+        );
+
+        let config = ParseConfig::new().allow_non_finite(true);
+        assert_eq!(
+            parse_with_config("1e400", &config),
+            Ok(Some(Value::Number(f64::INFINITY)))
+        );
+        assert_eq!(
+            parse_with_config("-1e400", &config),
+            Ok(Some(Value::Number(f64::NEG_INFINITY)))
+        );
+        assert_eq!(
+            parse_with_config(&huge, &config),
+            Ok(Some(Value::Number(f64::INFINITY)))
+        );
+    }
+
+    #[test]
+    fn big_integer_values() {
+        // The variant works without the `i128` feature, it's just not parsed.
+        let value = Value::BigInteger(i128::MAX);
+        assert_eq!(value.to_json_string(), i128::MAX.to_string());
+        assert_eq!(value.as_i128(), Some(i128::MAX));
+        assert_eq!(OwnedValue::from(value.clone()).borrow(), value);
+    }
+
+    #[cfg(feature = "i128")]
+    #[test]
+    fn big_integers() {
+        let id = "1234567890123456789012";
+        let value = parse(id).unwrap().unwrap();
+        assert_eq!(value, Value::BigInteger(1_234_567_890_123_456_789_012));
+        assert_eq!(value.as_i128(), Some(1_234_567_890_123_456_789_012));
+        assert_eq!(value.to_json_string(), id);
+        assert!(i64::try_from(value).is_err());
+
+        assert_eq!(parse("-7"), Ok(Some(Value::Integer(-7))));
+        assert_eq!(
+            parse(&i128::MIN.to_string()),
+            Ok(Some(Value::BigInteger(i128::MIN)))
+        );
+        // Beyond `i128`, and with a fraction or exponent, it's an `f64` again.
+        assert_eq!(
+            parse(&u128::MAX.to_string()).unwrap().unwrap().as_i128(),
+            None
+        );
+        assert_eq!(parse("1e30"), Ok(Some(Value::Number(1e30))));
+    }
+
+    #[test]
+    fn size_limits() {
+        let config = ParseConfig::new().max_input_bytes(8);
+        assert_eq!(
+            parse_with_config("[1, 2, 3]", &config).unwrap_err().kind,
+            ErrorKind::InputTooLarge
+        );
+        let error = parse_with_config("[1,2,3]  ", &config).unwrap_err();
+        assert_eq!(
+            (error.message.as_str(), error.byte_offset),
+            ("input is larger than 8 bytes", 0)
+        );
+        assert!(parse_with_config("[1,2,3] ", &config).is_ok());
+        // One error, then the tokenizer ends.
+        let mut tokens = Tokenizer::with_config("[1, 2, 3]", config.clone());
+        assert_eq!(
+            tokens.next().unwrap().unwrap_err().kind,
+            ErrorKind::InputTooLarge
+        );
+        assert_eq!(tokens.next(), None);
+        assert_eq!(Tokenizer::with_config("[1, 2, 3]", config).count(), 1);
+
+        let config = ParseConfig::new().max_string_bytes(3);
+        assert!(parse_with_config(r#"{"abc": "\u00e9"}"#, &config).is_ok());
+        for (src, offset) in [
+            (r#"["abcd"]"#, 1),
+            (r#"{"abcd": 1}"#, 1),
+            (r#"[1, "\u00e9\u00e9"]"#, 4),
+            // Checked while decoding, before the string ends.
+            (r#"["\n\n\n\n and no end"#, 1),
+        ] {
+            let error = parse_with_config(src, &config).unwrap_err();
+            assert_eq!(
+                (error.kind, error.message.as_str(), error.byte_offset),
+                (
+                    ErrorKind::StringTooLong,
+                    "string is longer than 3 bytes",
+                    offset
+                ),
+                "{src}"
+            );
+        }
+    }
+
+    #[test]
+    fn raw_numbers() {
+        let src = r#"[1.0, 1e2, -0, 12345678901234567890123, 7, "8"]"#;
+        let config = ParseConfig::new().raw_numbers(true);
+        let value = parse_with_config(src, &config).unwrap().unwrap();
+        let raw: Vec<_> = value.iter_array().map(Value::raw_number).collect();
+        assert_eq!(
+            raw,
+            [
+                Some("1.0"),
+                Some("1e2"),
+                Some("-0"),
+                Some("12345678901234567890123"),
+                Some("7"),
+                None
+            ]
+        );
+        assert_eq!(value.to_json_string(), src.replace(", ", ","));
+
+        assert!(value[0].is_number());
+        assert_eq!(value[1].as_f64(), Some(100.0));
+        assert_eq!(i64::try_from(value[4].clone()), Ok(7));
+        assert_eq!(value[3].as_i128(), Some(12_345_678_901_234_567_890_123));
+        assert_eq!(value[0].as_i128(), None);
+        assert_eq!(value[0].to_canonical_string(), "1.0");
+        assert_eq!(value[1].to_canonical_json(), "100");
+
+        // Invalid literals still fail, but out of range ones are kept.
+        assert!(parse_with_config("01", &config).is_err());
+        let huge = parse_with_config("[1e400]", &config).unwrap().unwrap();
+        assert_eq!(huge[0].raw_number(), Some("1e400"));
+        assert_eq!(huge.to_json_string(), "[1e400]");
+        assert_eq!(
+            parse_with_config("NaN", &config.allow_non_finite(true))
+                .unwrap()
+                .unwrap()
+                .raw_number(),
+            None
+        );
     }
 
     #[test]
     fn parse_string() {
-        assert_eq!(parse("\"asd\""), Some((Some(Value::String("asd")), None)));
+        assert_eq!(parse("\"asd\""), Ok(Some(Value::String("asd".into()))));
+    }
+
+    #[test]
+    fn parse_escapes() {
+        let parsed = parse("\"a\\n\\t\\r\\\\\\\"\\/\\b\\fz\"").unwrap();
+        assert_eq!(
+            parsed,
+            Some(Value::String("a\n\t\r\\\"/\u{08}\u{0c}z".into()))
+        );
+
+        assert!(matches!(
+            parse("\"plain\""),
+            Ok(Some(Value::String(Cow::Borrowed("plain"))))
+        ));
+
+        let object = parse_complete("{\"a\\\"b\": 1}").unwrap();
+        assert_eq!(object.get("a\"b"), Some(&Value::Integer(1)));
+
+        assert_eq!(error_at("\"\\x\""), ("invalid escape".into(), 2, Some('x')));
+    }
+
+    #[test]
+    fn parse_unicode_escapes() {
+        assert_eq!(
+            parse_complete("\"\\u0041\\u00e9\\u00E9\\u4e2Dz\""),
+            Ok(Value::String("A\u{e9}\u{e9}\u{4e2d}z".into()))
+        );
+        assert_eq!(
+            error_at("\"\\u00G1\""),
+            ("expected hex digit in unicode escape".into(), 5, Some('G'))
+        );
+        assert_eq!(
+            error_at("\"\\u00"),
+            ("unterminated unicode escape".into(), 5, None)
+        );
+        assert_eq!(
+            error_at("\"\\uZZZZ\""),
+            ("expected hex digit in unicode escape".into(), 3, Some('Z'))
+        );
+
+        // Four hex digits right at the end of the input are a complete escape.
+        assert_eq!(parse("\"\\u0041\""), Ok(Some(Value::String("A".into()))));
+        assert_eq!(
+            error_at("\"\\u0041"),
+            ("unterminated string".into(), 7, None)
+        );
+    }
+
+    #[test]
+    fn parse_surrogate_pairs() {
+        assert_eq!(
+            parse_complete("\"\\uD800\\uDC00 \\udbff\\udfff\""),
+            Ok(Value::String("\u{10000} \u{10ffff}".into()))
+        );
+        assert_eq!(
+            parse_complete("\"smile \\uD83D\\uDE00!\""),
+            Ok(Value::String("smile \u{1f600}!".into()))
+        );
+
+        for src in [
+            "\"\\uD800\"",
+            "\"\\uD800x\"",
+            "\"\\uD800\\u0041\"",
+            "\"\\uDC00\"",
+            "\"\\uDC00\\uD800\"",
+        ] {
+            assert_eq!(
+                error_at(src),
+                ("unpaired surrogate in unicode escape".into(), 1, Some('\\'))
+            );
+        }
+    }
+
+    fn error_at(src: &str) -> (String, usize, Option<char>) {
+        let error = parse(src).unwrap_err();
+        (error.message, error.byte_offset, error.found)
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            error_at("[1, 2"),
+            ("expected ',' or ']' after array element".into(), 5, None)
+        );
+        assert_eq!(
+            error_at("{\"a\" 1}"),
+            ("expected ':' after object key".into(), 5, Some('1'))
+        );
+        assert_eq!(
+            error_at("  01"),
+            ("leading zeros are not allowed".into(), 3, Some('1'))
+        );
+        assert_eq!(
+            error_at("[1,]"),
+            ("unexpected character ']'".into(), 3, Some(']'))
+        );
+        assert_eq!(error_at("\"abc"), ("unterminated string".into(), 4, None));
+        assert_eq!(error_at("[ "), ("unexpected end of input".into(), 2, None));
+    }
+
+    #[test]
+    fn parse_complete_documents() {
+        assert_eq!(parse("123 456"), Ok(Some(Value::Integer(123))));
+        assert_eq!(parse_complete(" 123 \n\t"), Ok(Value::Integer(123)));
+
+        let error = parse_complete("123 456").unwrap_err();
+        assert_eq!(error.message, "unexpected trailing characters");
+        assert_eq!(error.byte_offset, 4);
+
+        assert!(parse_complete("").is_err());
+        assert!(parse_complete(" \n ").is_err());
+    }
+
+    #[test]
+    fn parse_strict_is_parse_complete() {
+        assert_eq!(parse_strict(" true \n"), Ok(Value::Bool(true)));
+
+        let error = parse_strict("true garbage").unwrap_err();
+        assert_eq!(error.message, "unexpected trailing characters");
+        assert_eq!(error.byte_offset, 5);
+
+        assert_eq!(
+            parse_strict("  ").unwrap_err().message,
+            "unexpected end of input"
+        );
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let src = "{\"a\": 1, \"b\": 2, \"a\": 3}";
+        assert_eq!(parse_complete(src).unwrap()["a"], Value::Integer(3));
+
+        let config = ParseConfig {
+            duplicate_key_behavior: DuplicateKeyBehavior::Error,
+            ..ParseConfig::default()
+        };
+        let error = parse_with_config(src, &config).unwrap_err();
+        assert_eq!(error.message, "duplicate key \"a\"");
+        assert_eq!(error.byte_offset, 17);
+
+        assert!(parse_with_config("[{\"a\": 1}, {\"a\": 2}]", &config).is_ok());
+
+        let config = ParseConfig::new().duplicate_key_behavior(DuplicateKeyBehavior::UseFirst);
+        let value = parse_with_config(src, &config).unwrap().unwrap();
+        assert_eq!(value["a"], Value::Integer(1));
+        assert_eq!(value.len(), Some(2));
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        assert_eq!(parse("\u{feff}true"), Ok(Some(Value::Bool(true))));
+        assert_eq!(parse_complete("\u{feff} null "), Ok(Value::Null));
+        assert_eq!(
+            parse_bytes(b"\xef\xbb\xbf[]"),
+            Ok(Some(Value::Array(vec![])))
+        );
+        assert_eq!(parse("\u{feff}"), Ok(None));
+
+        let error = parse("\u{feff}[x]").unwrap_err();
+        assert_eq!((error.byte_offset, error.found), (4, Some('x')));
+
+        // Only at the very start, and never inside a string.
+        assert!(parse(" \u{feff}true").is_err());
+        assert_eq!(
+            parse("\u{feff}\"\u{feff}x\""),
+            Ok(Some(Value::String("\u{feff}x".into())))
+        );
+        assert!(parse_complete("[]\u{feff}").is_err());
+    }
+
+    #[test]
+    fn parse_cow_input() {
+        let borrowed = parse_cow(Cow::Borrowed("[\"x\"]")).unwrap().unwrap();
+        assert!(matches!(&borrowed[0], Value::String(Cow::Borrowed("x"))));
+
+        let owned: Value<'static> = parse_cow(Cow::Owned("{\"k\": \"v\"} tail".to_owned()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(owned["k"], Value::String("v".into()));
+
+        assert_eq!(parse_cow(Cow::Owned(" ".to_owned())), Ok(None));
+        assert!(parse_cow(Cow::Owned("[".to_owned())).is_err());
+    }
+
+    #[test]
+    fn parse_from_bytes() {
+        assert_eq!(
+            parse_bytes(b" [\"\xc3\xa9\"]"),
+            Ok(Some(Value::Array(vec![Value::String("\u{e9}".into())])))
+        );
+
+        let error = parse_bytes(b"[\"a\",\n \"\xff\"]").unwrap_err();
+        assert_eq!(error.message, "invalid UTF-8");
+        assert_eq!((error.byte_offset, error.line, error.column), (8, 2, 3));
+    }
+
+    #[test]
+    fn max_depth() {
+        let deep = "[".repeat(100_000);
+        let error = parse(&deep).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::DepthLimitExceeded);
+        assert_eq!(error.byte_offset, 128);
+
+        let config = ParseConfig {
+            max_depth: 3,
+            ..ParseConfig::default()
+        };
+        assert!(parse_with_config("[{\"a\": [1]}]", &config).is_ok());
+        // Siblings do not add up.
+        assert!(parse_with_config("[[[1]], [[2]], {\"a\": [3]}]", &config).is_ok());
+
+        let error = parse_with_config("[[{\"a\": [1]}]]", &config).unwrap_err();
+        assert_eq!(error.message, "maximum nesting depth exceeded");
+        assert_eq!(error.byte_offset, 8);
+
+        let config = ParseConfig {
+            max_depth: 0,
+            ..ParseConfig::default()
+        };
+        assert_eq!(parse_with_config("1", &config), Ok(Some(Value::Integer(1))));
+        assert!(parse_with_config("[]", &config).is_err());
+    }
+
+    #[test]
+    fn default_max_depth_boundary() {
+        let nested = |depth: usize| "{\"a\":".repeat(depth) + "null" + &"}".repeat(depth);
+
+        assert_eq!(ParseConfig::default().max_depth, 128);
+        assert!(parse_complete(&nested(128)).is_ok());
+        assert_eq!(
+            parse_complete(&nested(129)).unwrap_err().kind,
+            ErrorKind::DepthLimitExceeded
+        );
+    }
+
+    #[test]
+    fn trailing_commas() {
+        let config = ParseConfig {
+            allow_trailing_commas: true,
+            ..ParseConfig::default()
+        };
+        let parse = |src| parse_with_config(src, &config).map(Option::unwrap);
+
+        assert_eq!(parse("[1, 2,]"), parse("[1, 2]"));
+        assert_eq!(parse("{\"a\": [{},] , }"), parse("{\"a\": [{}]}"));
+        for (src, offset) in [("[,]", 1), ("[1,,]", 3), ("{,}", 1), ("{\"a\": 1,,}", 8)] {
+            assert_eq!(parse(src).unwrap_err().byte_offset, offset, "{src}");
+        }
+        assert_eq!(
+            parse("[[1,],{\"a\":[2,{},],},[],]"),
+            parse("[[1],{\"a\":[2,{}]},[]]")
+        );
+
+        let with_comments = ParseConfig {
+            allow_comments: true,
+            ..config.clone()
+        };
+        assert_eq!(
+            parse_with_config("[1, // last\n]", &with_comments),
+            Ok(Some(Value::Array(vec![Value::Integer(1)])))
+        );
+
+        assert_eq!(
+            error_at("[1,]"),
+            ("unexpected character ']'".into(), 3, Some(']'))
+        );
+        assert_eq!(
+            error_at("{\"a\": 1,}"),
+            ("expected string key".into(), 8, Some('}'))
+        );
+    }
+
+    #[test]
+    fn non_finite_numbers() {
+        let config = ParseConfig::new().allow_non_finite(true);
+        let parse = |src| parse_with_config(src, &config).map(Option::unwrap);
+
+        assert_eq!(parse("Infinity"), Ok(Value::Number(f64::INFINITY)));
+        assert_eq!(parse("-Infinity"), Ok(Value::Number(f64::NEG_INFINITY)));
+        assert!(parse("NaN").unwrap().as_f64().unwrap().is_nan());
+        assert!(parse("-NaN").unwrap().as_f64().unwrap().is_nan());
+        assert_eq!(
+            parse("[-Infinity, 1, -2]"),
+            Ok(Value::Array(vec![
+                Value::Number(f64::NEG_INFINITY),
+                Value::Integer(1),
+                Value::Integer(-2)
+            ]))
+        );
+        assert_eq!(parse("Infinityx").unwrap_err().byte_offset, 8);
+        assert_eq!(parse("-Inf").unwrap_err().message, "expected digit");
+
+        assert_eq!(
+            error_at("NaN"),
+            ("unexpected character 'N'".into(), 0, Some('N'))
+        );
+        assert_eq!(
+            error_at("Infinity"),
+            ("unexpected character 'I'".into(), 0, Some('I'))
+        );
+        assert_eq!(
+            error_at("-Infinity"),
+            ("expected digit".into(), 1, Some('I'))
+        );
+        assert_eq!(error_at("-NaN"), ("expected digit".into(), 1, Some('N')));
+
+        // There is no JSON for them, so they are written as `null`.
+        assert_eq!(
+            parse("[NaN, Infinity]").unwrap().to_json_string(),
+            "[null,null]"
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let config = ParseConfig {
+            allow_comments: true,
+            ..ParseConfig::default()
+        };
+        let parse = |src| parse_with_config(src, &config);
+
+        let src =
+            "// settings\n{\r\n  \"a\": /* inline */ [1, // one\n 2]\n  /* \"b\": 3, */\n}// end";
+        assert_eq!(
+            parse(src),
+            Ok(Some(Value::Object(Map::from([(
+                "a".into(),
+                Value::Array(vec![Value::Integer(1), Value::Integer(2)])
+            )]))))
+        );
+        assert_eq!(
+            parse("{\"a\"/*1*/:/*2*/1/*3*/,//4\n\"b\"//5\n:[/*6*/]/*7*/}"),
+            parse("{\"a\": 1, \"b\": []}")
+        );
+        assert_eq!(parse("/* nothing */ // here"), Ok(None));
+        assert_eq!(
+            parse("[\"// not a comment\"]").unwrap().unwrap()[0].as_str(),
+            Some("// not a comment")
+        );
+
+        let error = parse("[1, /* open").unwrap_err();
+        assert_eq!(
+            (error.message.as_str(), error.byte_offset),
+            ("unterminated block comment", 4)
+        );
+        assert_eq!(parse("[1 / 2]").unwrap_err().byte_offset, 3);
+        assert_eq!(
+            error_at("[1, // comment\n2]"),
+            ("unexpected character '/'".into(), 4, Some('/'))
+        );
+    }
+
+    #[test]
+    fn deep_nesting_uses_no_stack() {
+        // Unterminated, so the result never has to be dropped recursively.
+        let depth = 1_000_000;
+        let src = "[{\"a\":".repeat(depth);
+        let config = ParseConfig {
+            max_depth: usize::MAX,
+            ..ParseConfig::default()
+        };
+
+        let error = parse_with_config(&src, &config).unwrap_err();
+        assert_eq!(error.message, "unexpected end of input");
+        assert_eq!(error.byte_offset, src.len());
+    }
+
+    #[test]
+    fn error_position() {
+        assert_eq!(position_of("abc", 0), (1, 1));
+        assert_eq!(position_of("a\nbc", 3), (2, 2));
+        assert_eq!(position_of("a\n\u{e9}b", 4), (2, 2));
+        assert_eq!(position_of("a\r\nb", 3), (2, 1));
+        assert_eq!(position_of("a\r\r\nb", 4), (3, 1));
+        assert_eq!(position_of("a\rb\nc", 4), (3, 1));
+        assert_eq!(position_of("ab", 10), (1, 3));
+
+        let error = parse("{\n  \"a\": [1,\n    2\n  }").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 3));
+        assert_eq!(
+            error.to_string(),
+            "parse error at line 4, column 3: expected ',' or ']' after array element"
+        );
+
+        let error = parse("[x]").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 2));
+
+        let error = parse("[\r\n  1,\r\n  2\r\n  x").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 3));
+
+        let error = parse("[\"\u{e9}\u{4e2d}\u{1f600}\" x]").unwrap_err();
+        assert_eq!((error.byte_offset, error.line, error.column), (13, 1, 8));
     }
 }
@@ -0,0 +1,155 @@
+/// Builds a [`Value`](crate::Value) from JSON-like syntax.
+///
+/// Arrays, objects and `null` are written as in JSON. Anything else is an
+/// expression converted with `Value::from`, so variables and other values
+/// can be interpolated. Object keys are string literals, or any expression
+/// that converts into a `Cow<str>` when wrapped in parentheses.
+///
+/// String literals are borrowed, so the result is a `Value<'static>` unless
+/// it interpolates borrowed data. Call `.into_owned()` on it to get an
+/// [`OwnedValue`](crate::OwnedValue).
+#[macro_export]
+macro_rules! json {
+    ($($json:tt)+) => {
+        $crate::__json_value!($($json)+)
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __json_value {
+    (null) => {
+        $crate::Value::Null
+    };
+    ([]) => {
+        $crate::Value::Array(::std::vec::Vec::new())
+    };
+    ([ $($elements:tt)+ ]) => {
+        $crate::Value::Array($crate::__json_array!([] $($elements)+))
+    };
+    ({}) => {
+        $crate::Value::Object($crate::Map::new())
+    };
+    ({ $($entries:tt)+ }) => {
+        $crate::Value::Object({
+            let mut map = $crate::Map::new();
+            $crate::__json_object!(map $($entries)+);
+            map
+        })
+    };
+    ($other:expr) => {
+        $crate::Value::from($other)
+    };
+}
+
+/// Munches array elements one at a time, collecting the finished ones
+/// inside the leading brackets.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __json_array {
+    ([ $($done:expr,)* ]) => {
+        ::std::vec![$($done),*]
+    };
+    ([ $($done:expr,)* ] null $(, $($rest:tt)*)?) => {
+        $crate::__json_array!([ $($done,)* $crate::__json_value!(null), ] $($($rest)*)?)
+    };
+    ([ $($done:expr,)* ] [ $($array:tt)* ] $(, $($rest:tt)*)?) => {
+        $crate::__json_array!([ $($done,)* $crate::__json_value!([ $($array)* ]), ] $($($rest)*)?)
+    };
+    ([ $($done:expr,)* ] { $($object:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::__json_array!([ $($done,)* $crate::__json_value!({ $($object)* }), ] $($($rest)*)?)
+    };
+    ([ $($done:expr,)* ] $next:expr $(, $($rest:tt)*)?) => {
+        $crate::__json_array!([ $($done,)* $crate::__json_value!($next), ] $($($rest)*)?)
+    };
+}
+
+/// Munches object entries one at a time, inserting each into `$map`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __json_object {
+    ($map:ident) => {};
+    ($map:ident $key:tt : null $(, $($rest:tt)*)?) => {
+        $crate::__json_object!(@insert $map $key ($crate::__json_value!(null)));
+        $crate::__json_object!($map $($($rest)*)?);
+    };
+    ($map:ident $key:tt : [ $($array:tt)* ] $(, $($rest:tt)*)?) => {
+        $crate::__json_object!(@insert $map $key ($crate::__json_value!([ $($array)* ])));
+        $crate::__json_object!($map $($($rest)*)?);
+    };
+    ($map:ident $key:tt : { $($object:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::__json_object!(@insert $map $key ($crate::__json_value!({ $($object)* })));
+        $crate::__json_object!($map $($($rest)*)?);
+    };
+    ($map:ident $key:tt : $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__json_object!(@insert $map $key ($crate::__json_value!($value)));
+        $crate::__json_object!($map $($($rest)*)?);
+    };
+    (@insert $map:ident $key:tt ($value:expr)) => {
+        $map.insert(::std::borrow::Cow::<str>::from($key), $value);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::{parse_complete, Map, Value};
+
+    #[test]
+    fn matches_parsed_json() {
+        let value: Value<'static> = json!({
+            "name": "x",
+            "nums": [1, 2, 3],
+            "ok": true,
+            "nested": {"empty": [], "none": null, "obj": {}},
+            "mixed": [null, [1.5], {"a": -1}, "s"]
+        });
+        assert_eq!(
+            value,
+            parse_complete(
+                r#"{
+                    "name": "x",
+                    "nums": [1, 2, 3],
+                    "ok": true,
+                    "nested": {"empty": [], "none": null, "obj": {}},
+                    "mixed": [null, [1.5], {"a": -1}, "s"]
+                }"#
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn interpolates_expressions() {
+        let name = String::from("dynamic");
+        let count = 2;
+        let inner = json!([true]);
+
+        let value = json!({
+            "count": count * 10,
+            (name.clone()): inner,
+            "label": name.as_str(),
+            "missing": None::<bool>,
+        });
+
+        assert_eq!(
+            value,
+            Value::Object(Map::from([
+                (Cow::from("count"), Value::Integer(20)),
+                (Cow::from("dynamic"), Value::Array(vec![Value::Bool(true)])),
+                (Cow::from("label"), Value::String("dynamic".into())),
+                (Cow::from("missing"), Value::Null),
+            ]))
+        );
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(json!(null), Value::Null);
+        assert_eq!(json!(false), Value::Bool(false));
+        assert_eq!(json!("s"), Value::String("s".into()));
+        assert_eq!(json!([]), Value::Array(vec![]));
+        assert_eq!(json!({}), Value::Object(Map::new()));
+    }
+}
@@ -0,0 +1,316 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+#[cfg(all(feature = "preserve-order", feature = "sorted-keys"))]
+compile_error!("the `preserve-order` and `sorted-keys` features are mutually exclusive");
+
+#[cfg(not(any(feature = "preserve-order", feature = "sorted-keys")))]
+use std::collections::hash_map as backend;
+#[cfg(not(any(feature = "preserve-order", feature = "sorted-keys")))]
+type Inner<K, V> = backend::HashMap<K, V>;
+
+#[cfg(feature = "preserve-order")]
+use indexmap::map as backend;
+#[cfg(feature = "preserve-order")]
+type Inner<K, V> = backend::IndexMap<K, V>;
+
+#[cfg(all(feature = "sorted-keys", not(feature = "preserve-order")))]
+use std::collections::btree_map as backend;
+#[cfg(all(feature = "sorted-keys", not(feature = "preserve-order")))]
+type Inner<K, V> = backend::BTreeMap<K, V>;
+
+/// The map behind JSON objects.
+///
+/// Iteration order is unspecified by default. With the `preserve-order`
+/// feature it is the order in which keys were first inserted, which for
+/// parsed objects is the order they appear in the input. With the
+/// `sorted-keys` feature it is the keys' lexicographic order.
+#[derive(Clone)]
+pub struct Map<K, V> {
+    inner: Inner<K, V>,
+}
+
+impl<K, V> Map<K, V> {
+    pub fn new() -> Self {
+        Self {
+            inner: Inner::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.inner.iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.inner.iter_mut(),
+        }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            inner: self.inner.keys(),
+        }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            inner: self.inner.values(),
+        }
+    }
+
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            inner: self.inner.values_mut(),
+        }
+    }
+}
+
+impl<K: Borrow<str> + Hash + Ord, V> Map<K, V> {
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.inner.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        self.inner.get_mut(key)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.inner.contains_key(key)
+    }
+
+    /// Inserts `value` under `key`, returning the previous value for `key`.
+    ///
+    /// Replacing a value keeps the key in its original position.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.inner.insert(key, value)
+    }
+
+    /// Removes `key`, keeping the order of the remaining entries.
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        #[cfg(feature = "preserve-order")]
+        return self.inner.shift_remove(key);
+        #[cfg(not(feature = "preserve-order"))]
+        return self.inner.remove(key);
+    }
+
+    pub fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.inner.retain(f)
+    }
+
+    /// Sorts the entries by key, which only affects iteration order with
+    /// the `preserve-order` feature. Without any ordering feature there is
+    /// no order to change, and with `sorted-keys` the keys are always
+    /// sorted.
+    pub fn sort_keys(&mut self) {
+        #[cfg(feature = "preserve-order")]
+        self.inner.sort_keys();
+    }
+}
+
+impl<K, V> Default for Map<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Map<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Maps are equal when they have the same entries, in any order.
+impl<K: Hash + Ord, V: PartialEq> PartialEq for Map<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<K: Hash + Ord, V: Eq> Eq for Map<K, V> {}
+
+/// Hashes the entries in an order-independent way, consistent with `==`.
+impl<K: Hash, V: Hash> Hash for Map<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Combine the entries' own hashes with a commutative operation, so
+        // iteration order doesn't matter.
+        let combined = self.iter().fold(0u64, |sum, entry| {
+            let mut hasher = DefaultHasher::new();
+            entry.hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        });
+        self.len().hash(state);
+        combined.hash(state);
+    }
+}
+
+impl<K: Hash + Ord, V> FromIterator<(K, V)> for Map<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self {
+            inner: Inner::from_iter(iter),
+        }
+    }
+}
+
+impl<K: Hash + Ord, V> Extend<(K, V)> for Map<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.inner.extend(iter)
+    }
+}
+
+impl<K: Hash + Ord, V, const N: usize> From<[(K, V); N]> for Map<K, V> {
+    fn from(entries: [(K, V); N]) -> Self {
+        Self::from_iter(entries)
+    }
+}
+
+impl<K, V> IntoIterator for Map<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.inner.into_iter(),
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a Map<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut Map<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Defines a wrapper around one of the backend's iterators, so the backend
+/// doesn't leak into the public API.
+macro_rules! wrap_iter {
+    ($(#[$doc:meta])* $name:ident<$($lt:lifetime,)? K, V> => $item:ty) => {
+        $(#[$doc])*
+        pub struct $name<$($lt,)? K, V> {
+            inner: backend::$name<$($lt,)? K, V>,
+        }
+
+        impl<$($lt,)? K, V> Iterator for $name<$($lt,)? K, V> {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl<$($lt,)? K, V> ExactSizeIterator for $name<$($lt,)? K, V> {}
+    };
+}
+
+wrap_iter!(
+    /// Iterator over the entries of a [`Map`].
+    Iter<'a, K, V> => (&'a K, &'a V)
+);
+wrap_iter!(
+    /// Mutable iterator over the entries of a [`Map`].
+    IterMut<'a, K, V> => (&'a K, &'a mut V)
+);
+wrap_iter!(
+    /// Owning iterator over the entries of a [`Map`].
+    IntoIter<K, V> => (K, V)
+);
+wrap_iter!(
+    /// Iterator over the keys of a [`Map`].
+    Keys<'a, K, V> => &'a K
+);
+wrap_iter!(
+    /// Iterator over the values of a [`Map`].
+    Values<'a, K, V> => &'a V
+);
+wrap_iter!(
+    /// Mutable iterator over the values of a [`Map`].
+    ValuesMut<'a, K, V> => &'a mut V
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map = Map::new();
+        assert_eq!(map.insert("a".to_owned(), 1), None);
+        assert_eq!(map.insert("a".to_owned(), 2), Some(1));
+        assert_eq!(map.get("a"), Some(&2));
+        assert!(map.contains_key("a"));
+        assert_eq!(map.remove("a"), Some(2));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn equality_ignores_order() {
+        let a = Map::from([("a".to_owned(), 1), ("b".to_owned(), 2)]);
+        let b = Map::from([("b".to_owned(), 2), ("a".to_owned(), 1)]);
+        assert_eq!(a, b);
+    }
+
+    #[cfg(feature = "preserve-order")]
+    #[test]
+    fn preserves_insertion_order() {
+        let mut map: Map<String, i32> = ["z", "a", "m", "b"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, k)| (k.to_owned(), i as i32))
+            .collect();
+        map.remove("a");
+        map.insert("z".to_owned(), 9);
+        let keys: Vec<_> = map.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["z", "m", "b"]);
+    }
+
+    #[cfg(feature = "sorted-keys")]
+    #[test]
+    fn sorted_keys() {
+        let mut map: Map<String, i32> = ["z", "a", "m", "b"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, k)| (k.to_owned(), i as i32))
+            .collect();
+        map.insert("c".to_owned(), 9);
+        let keys: Vec<_> = map.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["a", "b", "c", "m", "z"]);
+    }
+
+    #[test]
+    fn sort_keys() {
+        let mut map = Map::from([("b".to_owned(), 1), ("a".to_owned(), 2)]);
+        map.sort_keys();
+        assert_eq!(map, Map::from([("a".to_owned(), 2), ("b".to_owned(), 1)]));
+
+        #[cfg(any(feature = "preserve-order", feature = "sorted-keys"))]
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["a", "b"]);
+    }
+}
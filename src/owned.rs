@@ -0,0 +1,204 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use crate::{parse_complete, Map, ParseError, Value};
+
+/// A JSON value that owns all of its data.
+///
+/// Mirrors [`Value`] without borrowing from the parsed input, so it can be
+/// stored and moved around after the source buffer is gone.
+///
+/// Compares and hashes like [`Value`].
+#[derive(Debug, Clone)]
+pub enum OwnedValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Integer(i64),
+    BigInteger(i128),
+    RawNumber(String),
+    String(String),
+    Array(Vec<OwnedValue>),
+    Object(Map<String, OwnedValue>),
+}
+
+impl OwnedValue {
+    /// Returns a [`Value`] borrowing from `self`.
+    pub fn borrow(&self) -> Value<'_> {
+        match self {
+            Self::Null => Value::Null,
+            Self::Bool(b) => Value::Bool(*b),
+            Self::Number(n) => Value::Number(*n),
+            Self::Integer(n) => Value::Integer(*n),
+            Self::BigInteger(n) => Value::BigInteger(*n),
+            Self::RawNumber(raw) => Value::RawNumber(Cow::Borrowed(raw)),
+            Self::String(s) => Value::String(Cow::Borrowed(s)),
+            Self::Array(arr) => Value::Array(arr.iter().map(Self::borrow).collect()),
+            Self::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (Cow::Borrowed(k.as_str()), v.borrow()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl OwnedValue {
+    /// Like [`Value::walk_mut`], calls `visit` on `self` and every value
+    /// nested in it, depth-first in pre-order.
+    pub fn walk_mut(&mut self, visit: &mut impl FnMut(&mut OwnedValue)) {
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            visit(value);
+            let start = stack.len();
+            match value {
+                Self::Array(arr) => stack.extend(arr),
+                Self::Object(map) => stack.extend(map.values_mut()),
+                _ => {}
+            }
+            stack[start..].reverse();
+        }
+    }
+}
+
+impl Value<'_> {
+    /// Copies all borrowed data out of the input, see [`OwnedValue`].
+    pub fn into_owned(self) -> OwnedValue {
+        OwnedValue::from(self)
+    }
+
+    /// Copies all borrowed data out of the input, keeping the result a
+    /// [`Value`].
+    pub fn into_static(self) -> Value<'static> {
+        match self {
+            Value::Null => Value::Null,
+            Value::Bool(b) => Value::Bool(b),
+            Value::Number(n) => Value::Number(n),
+            Value::Integer(n) => Value::Integer(n),
+            Value::BigInteger(n) => Value::BigInteger(n),
+            Value::RawNumber(raw) => Value::RawNumber(Cow::Owned(raw.into_owned())),
+            Value::String(s) => Value::String(Cow::Owned(s.into_owned())),
+            Value::Array(arr) => Value::Array(arr.into_iter().map(Value::into_static).collect()),
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(k, v)| (Cow::Owned(k.into_owned()), v.into_static()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<Value<'_>> for OwnedValue {
+    fn from(value: Value<'_>) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(b) => Self::Bool(b),
+            Value::Number(n) => Self::Number(n),
+            Value::Integer(n) => Self::Integer(n),
+            Value::BigInteger(n) => Self::BigInteger(n),
+            Value::RawNumber(raw) => Self::RawNumber(raw.into_owned()),
+            Value::String(s) => Self::String(s.into_owned()),
+            Value::Array(arr) => Self::Array(arr.into_iter().map(Self::from).collect()),
+            Value::Object(map) => Self::Object(
+                map.into_iter()
+                    .map(|(k, v)| (k.into_owned(), Self::from(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Parses a whole document like [`parse_complete`].
+///
+/// `Value` can't implement `FromStr` because it borrows from the string.
+impl FromStr for OwnedValue {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_complete(s).map(OwnedValue::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outlives_source() {
+        let owned = {
+            let src = String::from("{\"a\": [1, \"two\", null], \"b\": true}");
+            parse_complete(&src).unwrap().into_owned()
+        };
+
+        assert_eq!(
+            owned,
+            OwnedValue::Object(Map::from([
+                (
+                    "a".to_owned(),
+                    OwnedValue::Array(vec![
+                        OwnedValue::Integer(1),
+                        OwnedValue::String("two".to_owned()),
+                        OwnedValue::Null,
+                    ])
+                ),
+                ("b".to_owned(), OwnedValue::Bool(true)),
+            ]))
+        );
+    }
+
+    #[test]
+    fn from_str() -> Result<(), ParseError> {
+        let value: OwnedValue = " [true, \"x\"] ".parse()?;
+        assert_eq!(
+            value,
+            OwnedValue::Array(vec![
+                OwnedValue::Bool(true),
+                OwnedValue::String("x".to_owned())
+            ])
+        );
+        assert!("[1] [2]".parse::<OwnedValue>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_errors() {
+        let error = "{\"a\": 1} x".parse::<OwnedValue>().unwrap_err();
+        assert_eq!(error.message, "unexpected trailing characters");
+        assert_eq!(error.byte_offset, 9);
+
+        let error = "".parse::<OwnedValue>().unwrap_err();
+        assert_eq!(error.message, "unexpected end of input");
+    }
+
+    #[test]
+    fn into_static() {
+        let value: Value<'static> = {
+            let src = String::from("{\"a\": [\"b\", 2.5]}");
+            parse_complete(&src).unwrap().into_static()
+        };
+        assert_eq!(value["a"][0], Value::String("b".into()));
+        assert_eq!(value["a"][1], Value::Number(2.5));
+    }
+
+    #[test]
+    fn walk_mut() {
+        let mut owned: OwnedValue = r#"{"a": ["x", {"b": "y"}], "c": 1}"#.parse().unwrap();
+        owned.walk_mut(&mut |v| {
+            if let OwnedValue::String(s) = v {
+                s.make_ascii_uppercase();
+            }
+        });
+        assert_eq!(
+            owned,
+            r#"{"a": ["X", {"b": "Y"}], "c": 1}"#.parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn borrow_round_trip() {
+        let src = "[{\"k\": \"v\"}, 1.5, false]";
+        let value = parse_complete(src).unwrap();
+        let owned = OwnedValue::from(value.clone());
+        assert_eq!(owned.borrow(), value);
+    }
+}
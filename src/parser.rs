@@ -0,0 +1,92 @@
+use crate::{str_from_utf8, strip_bom, Context, ParseConfig, ParseError, Value};
+
+/// Parses many inputs with the same [`ParseConfig`].
+///
+/// `Parser::default()` accepts the same input as the free functions like
+/// [`parse`](crate::parse).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Parser {
+    config: ParseConfig,
+}
+
+impl Parser {
+    pub fn new(config: ParseConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &ParseConfig {
+        &self.config
+    }
+
+    /// Like [`parse_with_config`](crate::parse_with_config).
+    pub fn parse<'a>(&self, src: &'a str) -> Result<Option<Value<'a>>, ParseError> {
+        let parsed = Context::new(src, &self.config).parse_one(strip_bom(src))?;
+        Ok(parsed.map(|(value, _)| value))
+    }
+
+    /// Like [`parse_complete`](crate::parse_complete).
+    pub fn parse_complete<'a>(&self, src: &'a str) -> Result<Value<'a>, ParseError> {
+        Context::new(src, &self.config).parse_complete(strip_bom(src))
+    }
+
+    /// Like [`parse_bytes`](crate::parse_bytes).
+    pub fn parse_bytes<'a>(&self, src: &'a [u8]) -> Result<Option<Value<'a>>, ParseError> {
+        self.parse(str_from_utf8(src)?)
+    }
+}
+
+impl From<ParseConfig> for Parser {
+    fn from(config: ParseConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_with, DuplicateKeyBehavior, ErrorKind, ParseOptions};
+
+    #[test]
+    fn builder() {
+        let options = ParseOptions::new()
+            .duplicate_key_behavior(DuplicateKeyBehavior::Error)
+            .max_depth(2)
+            .allow_trailing_commas(true)
+            .allow_comments(true);
+        assert_eq!(
+            options,
+            ParseConfig {
+                duplicate_key_behavior: DuplicateKeyBehavior::Error,
+                max_depth: 2,
+                allow_trailing_commas: true,
+                allow_comments: true,
+                allow_non_finite: false,
+                raw_numbers: false,
+                max_input_bytes: None,
+                max_string_bytes: None,
+            }
+        );
+        assert_eq!(
+            parse_with("[1,] // x", &options),
+            Ok(Some(Value::Array(vec![Value::Integer(1)])))
+        );
+    }
+
+    #[test]
+    fn reusable_parser() {
+        let parser = Parser::new(ParseConfig::new().allow_comments(true).max_depth(1));
+
+        assert_eq!(parser.parse("/* a */ 1"), Ok(Some(Value::Integer(1))));
+        assert_eq!(
+            parser.parse_bytes(b"[2]"),
+            Ok(Some(Value::Array(vec![Value::Integer(2)])))
+        );
+        assert_eq!(parser.parse_complete("3 // end"), Ok(Value::Integer(3)));
+        assert_eq!(
+            parser.parse_complete("[[]]").unwrap_err().kind,
+            ErrorKind::DepthLimitExceeded
+        );
+        assert!(parser.parse_complete("3 4").is_err());
+        assert_eq!(Parser::default().config(), &ParseConfig::default());
+    }
+}
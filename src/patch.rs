@@ -0,0 +1,877 @@
+use std::fmt;
+
+use crate::pointer::{array_index, decode};
+use crate::value::parse_raw_number;
+use crate::{Map, OwnedValue, Value};
+
+impl<'a> Value<'a> {
+    /// Applies `patch` to `self` as an
+    /// [RFC 7396](https://www.rfc-editor.org/rfc/rfc7396) JSON Merge Patch.
+    ///
+    /// An object patch updates `self` key by key: `null` removes a key,
+    /// objects are merged recursively and anything else replaces the
+    /// existing value. If `self` is not an object it is replaced by an empty
+    /// one first. Any other patch replaces `self` entirely.
+    pub fn merge_patch(&mut self, patch: &Value<'a>) {
+        let Value::Object(changes) = patch else {
+            *self = patch.clone();
+            return;
+        };
+
+        if !self.is_object() {
+            *self = Value::Object(Map::new());
+        }
+        let Value::Object(target) = self else {
+            unreachable!("self was just made an object");
+        };
+
+        for (key, change) in changes {
+            if change.is_null() {
+                target.remove(key);
+            } else if let Some(existing) = target.get_mut(key) {
+                existing.merge_patch(change);
+            } else {
+                let mut value = Value::Null;
+                value.merge_patch(change);
+                target.insert(key.clone(), value);
+            }
+        }
+    }
+
+    /// Deep-merges `overlay` over `base`, e.g. user settings over defaults.
+    ///
+    /// Unlike [`Value::merge_patch`], nothing is ever removed:
+    ///  - two objects are merged key by key, merging the values of keys
+    ///    present in both recursively and keeping keys present in only one
+    ///  - two arrays are concatenated, `base`'s elements first
+    ///  - in every other case, including `null` and mismatched types,
+    ///    `overlay` wins
+    pub fn merge(base: Value<'a>, overlay: Value<'a>) -> Value<'a> {
+        match (base, overlay) {
+            (Value::Object(mut base), Value::Object(overlay)) => {
+                for (key, value) in overlay {
+                    match base.get_mut(&key) {
+                        Some(existing) => {
+                            let old = std::mem::replace(existing, Value::Null);
+                            *existing = Self::merge(old, value);
+                        }
+                        None => {
+                            base.insert(key, value);
+                        }
+                    }
+                }
+                Value::Object(base)
+            }
+            (Value::Array(mut base), Value::Array(overlay)) => {
+                base.extend(overlay);
+                Value::Array(base)
+            }
+            (_, overlay) => overlay,
+        }
+    }
+}
+
+/// One operation of an [RFC 6902](https://www.rfc-editor.org/rfc/rfc6902)
+/// JSON Patch, see [`Value::apply_patch`].
+///
+/// Paths are JSON Pointers, like for [`Value::pointer`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOp<'a> {
+    /// Inserts `value` at `path`, replacing an existing object member or
+    /// shifting later array elements. `-` as the last segment appends to an
+    /// array.
+    Add { path: String, value: Value<'a> },
+    /// Removes the value at `path`.
+    Remove { path: String },
+    /// Replaces the existing value at `path`.
+    Replace { path: String, value: Value<'a> },
+    /// Removes the value at `from` and adds it at `path`.
+    Move { from: String, path: String },
+    /// Adds a copy of the value at `from` at `path`.
+    Copy { from: String, path: String },
+    /// Fails unless the value at `path` equals `value`. Numbers are compared
+    /// by value, so `1` equals `1.0`.
+    Test { path: String, value: Value<'a> },
+}
+
+impl<'a> PatchOp<'a> {
+    /// Reads the operations of a JSON Patch document, an array of objects
+    /// like `{"op": "add", "path": "/a", "value": 1}`.
+    pub fn from_json(patch: &Value<'a>) -> Result<Vec<Self>, PatchError> {
+        let Value::Array(ops) = patch else {
+            return Err(PatchError::invalid(None, "patch is not an array"));
+        };
+
+        ops.iter()
+            .enumerate()
+            .map(|(index, op)| {
+                Self::from_json_op(op).map_err(|message| PatchError::invalid(Some(index), message))
+            })
+            .collect()
+    }
+
+    fn from_json_op(op: &Value<'a>) -> Result<Self, &'static str> {
+        if !op.is_object() {
+            return Err("operation is not an object");
+        }
+        let string = |member, missing| match op.get(member) {
+            Some(Value::String(s)) => Ok(s.to_string()),
+            _ => Err(missing),
+        };
+        let value = || op.get("value").cloned().ok_or("missing \"value\"");
+
+        let path = string("path", "missing \"path\"")?;
+        Ok(match string("op", "missing \"op\"")?.as_str() {
+            "add" => Self::Add {
+                path,
+                value: value()?,
+            },
+            "remove" => Self::Remove { path },
+            "replace" => Self::Replace {
+                path,
+                value: value()?,
+            },
+            "move" => Self::Move {
+                from: string("from", "missing \"from\"")?,
+                path,
+            },
+            "copy" => Self::Copy {
+                from: string("from", "missing \"from\"")?,
+                path,
+            },
+            "test" => Self::Test {
+                path,
+                value: value()?,
+            },
+            _ => return Err("unknown \"op\""),
+        })
+    }
+}
+
+/// Error returned by [`Value::apply_patch`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+    /// The patch document passed to [`PatchOp::from_json`] is malformed, at
+    /// the operation with the given index if there is one.
+    InvalidOperation {
+        index: Option<usize>,
+        message: &'static str,
+    },
+    /// `path` is not a valid JSON Pointer, or doesn't point at a location
+    /// the operation at `index` can use.
+    PathNotFound { index: usize, path: String },
+    /// A `move`, at `index` in the patch, from a location into one of its
+    /// own children.
+    MoveIntoChild {
+        index: usize,
+        from: String,
+        path: String,
+    },
+    /// The `test` operation at `index` found `actual` instead of `expected`.
+    TestFailed {
+        index: usize,
+        path: String,
+        expected: Box<OwnedValue>,
+        actual: Box<OwnedValue>,
+    },
+}
+
+impl PatchError {
+    fn invalid(index: Option<usize>, message: &'static str) -> Self {
+        Self::InvalidOperation { index, message }
+    }
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidOperation {
+                index: Some(index),
+                message,
+            } => {
+                write!(f, "invalid patch operation {index}: {message}")
+            }
+            Self::InvalidOperation {
+                index: None,
+                message,
+            } => {
+                write!(f, "invalid patch: {message}")
+            }
+            Self::PathNotFound { index, path } => {
+                write!(f, "patch operation {index} failed: path {path:?} not found")
+            }
+            Self::MoveIntoChild { index, from, path } => write!(
+                f,
+                "patch operation {index} failed: cannot move {from:?} into its child {path:?}"
+            ),
+            Self::TestFailed {
+                index,
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "patch operation {index} failed: test at {path:?} expected {}, found {}",
+                expected.borrow(),
+                actual.borrow()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+impl<'a> Value<'a> {
+    /// Applies the operations of an
+    /// [RFC 6902](https://www.rfc-editor.org/rfc/rfc6902) JSON Patch to
+    /// `self`, in order.
+    ///
+    /// The patch is atomic: if any operation fails, `self` is left
+    /// unchanged.
+    pub fn apply_patch(&mut self, ops: &[PatchOp<'a>]) -> Result<(), PatchError> {
+        let mut patched = self.clone();
+        for (index, op) in ops.iter().enumerate() {
+            patched.apply_op(index, op)?;
+        }
+        *self = patched;
+        Ok(())
+    }
+
+    fn apply_op(&mut self, index: usize, op: &PatchOp<'a>) -> Result<(), PatchError> {
+        let not_found = |path: &str| PatchError::PathNotFound {
+            index,
+            path: path.to_owned(),
+        };
+
+        match op {
+            PatchOp::Add { path, value } => self
+                .patch_add(path, value.clone())
+                .ok_or_else(|| not_found(path)),
+            PatchOp::Remove { path } => self
+                .patch_remove(path)
+                .map(drop)
+                .ok_or_else(|| not_found(path)),
+            PatchOp::Replace { path, value } => {
+                let target = self.pointer_mut(path).ok_or_else(|| not_found(path))?;
+                *target = value.clone();
+                Ok(())
+            }
+            PatchOp::Move { from, path } => {
+                if path == from {
+                    return Ok(());
+                }
+                if path.starts_with(from.as_str()) && path[from.len()..].starts_with('/') {
+                    return Err(PatchError::MoveIntoChild {
+                        index,
+                        from: from.clone(),
+                        path: path.clone(),
+                    });
+                }
+                let value = self.patch_remove(from).ok_or_else(|| not_found(from))?;
+                self.patch_add(path, value).ok_or_else(|| not_found(path))
+            }
+            PatchOp::Copy { from, path } => {
+                let value = self.pointer(from).ok_or_else(|| not_found(from))?;
+                self.patch_add(path, value.clone())
+                    .ok_or_else(|| not_found(path))
+            }
+            PatchOp::Test { path, value } => {
+                let actual = self.pointer(path).ok_or_else(|| not_found(path))?;
+                if numerically_eq(actual, value) {
+                    Ok(())
+                } else {
+                    Err(PatchError::TestFailed {
+                        index,
+                        path: path.clone(),
+                        expected: Box::new(value.clone().into_owned()),
+                        actual: Box::new(actual.clone().into_owned()),
+                    })
+                }
+            }
+        }
+    }
+
+    /// Adds `value` at `path`, returning `None` if there is no such
+    /// location.
+    fn patch_add(&mut self, path: &str, value: Value<'a>) -> Option<()> {
+        let Some((parent, last)) = path.rsplit_once('/') else {
+            return path.is_empty().then(|| *self = value);
+        };
+        let last = decode(last)?;
+
+        match self.pointer_mut(parent)? {
+            Value::Object(map) => {
+                map.insert(last.into_owned().into(), value);
+            }
+            Value::Array(arr) => {
+                let index = match last.as_ref() {
+                    "-" => arr.len(),
+                    token => array_index(token).filter(|&index| index <= arr.len())?,
+                };
+                arr.insert(index, value);
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Removes and returns the value at `path`, if there is one.
+    fn patch_remove(&mut self, path: &str) -> Option<Value<'a>> {
+        let Some((parent, last)) = path.rsplit_once('/') else {
+            return path
+                .is_empty()
+                .then(|| std::mem::replace(self, Value::Null));
+        };
+        let last = decode(last)?;
+
+        match self.pointer_mut(parent)? {
+            Value::Object(map) => map.remove(&last),
+            Value::Array(arr) => array_index(&last)
+                .filter(|&index| index < arr.len())
+                .map(|index| arr.remove(index)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Returns JSON Patch operations that turn `self` into `other`, so that
+    /// applying them with [`Value::apply_patch`] gives a value equal to
+    /// `other`.
+    ///
+    /// Objects are compared key by key and arrays index by index, recursing
+    /// into values present on both sides. The result is not necessarily
+    /// the shortest possible patch: inserting at the front of an array, for
+    /// example, replaces every element after it.
+    pub fn diff(&self, other: &Value<'a>) -> Vec<PatchOp<'a>> {
+        let mut ops = Vec::new();
+        diff_into(self, other, &mut String::new(), &mut ops);
+        ops
+    }
+
+    /// Lists every place where `self` and `other` differ, for asserting
+    /// what changed between two documents.
+    ///
+    /// Objects are compared key by key and arrays index by index, like for
+    /// [`Value::diff`], and anything else is compared with `==`. The result
+    /// is sorted by path, with the keys of an object in lexicographic order
+    /// and array indices in numeric order.
+    pub fn differences<'v>(&'v self, other: &'v Value<'a>) -> Vec<Difference<'v, 'a>> {
+        let mut differences = Vec::new();
+        differences_into(self, other, &mut String::new(), &mut differences);
+        differences
+    }
+}
+
+/// A place where two values differ, see [`Value::differences`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference<'v, 'a> {
+    /// JSON Pointer to the place, like `/users/0/name`.
+    pub path: String,
+    /// The value in the first document, `None` if it was added.
+    pub old: Option<&'v Value<'a>>,
+    /// The value in the second document, `None` if it was removed.
+    pub new: Option<&'v Value<'a>>,
+}
+
+fn differences_into<'v, 'a>(
+    old: &'v Value<'a>,
+    new: &'v Value<'a>,
+    path: &mut String,
+    differences: &mut Vec<Difference<'v, 'a>>,
+) {
+    let mut compare = |path: &mut String, old: Option<&'v Value<'a>>, new| match (old, new) {
+        (Some(old), Some(new)) => differences_into(old, new, path, differences),
+        (old, new) => differences.push(Difference {
+            path: path.clone(),
+            old,
+            new,
+        }),
+    };
+
+    if old == new {
+        return;
+    }
+
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys: Vec<&str> = old.keys().map(AsRef::as_ref).collect();
+            keys.extend(
+                new.keys()
+                    .map(AsRef::as_ref)
+                    .filter(|k| !old.contains_key(k)),
+            );
+            keys.sort_unstable();
+
+            for key in keys {
+                let len = path.len();
+                *path = child_path(path, key);
+                compare(path, old.get(key), new.get(key));
+                path.truncate(len);
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for i in 0..old.len().max(new.len()) {
+                let len = path.len();
+                *path = child_path(path, &i.to_string());
+                compare(path, old.get(i), new.get(i));
+                path.truncate(len);
+            }
+        }
+        _ => differences.push(Difference {
+            path: path.clone(),
+            old: Some(old),
+            new: Some(new),
+        }),
+    }
+}
+
+fn diff_into<'a>(from: &Value<'a>, to: &Value<'a>, path: &mut String, ops: &mut Vec<PatchOp<'a>>) {
+    if from == to {
+        return;
+    }
+
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            for key in from.keys().filter(|key| !to.contains_key(key)) {
+                ops.push(PatchOp::Remove {
+                    path: child_path(path, key),
+                });
+            }
+            for (key, value) in to {
+                match from.get(key) {
+                    Some(old) => {
+                        let len = path.len();
+                        *path = child_path(path, key);
+                        diff_into(old, value, path, ops);
+                        path.truncate(len);
+                    }
+                    None => ops.push(PatchOp::Add {
+                        path: child_path(path, key),
+                        value: value.clone(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(from), Value::Array(to)) => {
+            for (i, (old, new)) in from.iter().zip(to).enumerate() {
+                let len = path.len();
+                *path = child_path(path, &i.to_string());
+                diff_into(old, new, path, ops);
+                path.truncate(len);
+            }
+            for (i, value) in to.iter().enumerate().skip(from.len()) {
+                ops.push(PatchOp::Add {
+                    path: child_path(path, &i.to_string()),
+                    value: value.clone(),
+                });
+            }
+            // From the back, so each index is still valid when removed.
+            for i in (to.len()..from.len()).rev() {
+                ops.push(PatchOp::Remove {
+                    path: child_path(path, &i.to_string()),
+                });
+            }
+        }
+        _ => ops.push(PatchOp::Replace {
+            path: path.clone(),
+            value: to.clone(),
+        }),
+    }
+}
+
+/// Appends `token` to the JSON Pointer `parent`, escaping `~` and `/`.
+pub(crate) fn child_path(parent: &str, token: &str) -> String {
+    format!("{parent}/{}", token.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Compares like `==`, except that an `Integer` and a `Number` with the same
+/// numeric value are equal.
+fn numerically_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::RawNumber(raw), other) | (other, Value::RawNumber(raw)) => {
+            numerically_eq(&parse_raw_number(raw), other)
+        }
+        (Value::Integer(i), Value::Number(n)) | (Value::Number(n), Value::Integer(i)) => {
+            *i as f64 == *n && (*n as i64) == *i
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| numerically_eq(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(k, v)| b.get(k).is_some_and(|w| numerically_eq(v, w)))
+        }
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_complete;
+
+    /// Applies the JSON Patch `patch` to `doc`.
+    fn apply<'a>(doc: &'a str, patch: &'a str) -> Result<Value<'a>, PatchError> {
+        let mut value = parse_complete(doc).unwrap();
+        value.apply_patch(&PatchOp::from_json(&parse_complete(patch).unwrap())?)?;
+        Ok(value)
+    }
+
+    #[test]
+    fn json_patch_rfc_examples() {
+        // RFC 6902, appendix A.
+        for (doc, patch, result) in [
+            (
+                r#"{"foo":"bar"}"#,
+                r#"[{"op":"add","path":"/baz","value":"qux"}]"#,
+                r#"{"baz":"qux","foo":"bar"}"#,
+            ),
+            (
+                r#"{"foo":["bar","baz"]}"#,
+                r#"[{"op":"add","path":"/foo/1","value":"qux"}]"#,
+                r#"{"foo":["bar","qux","baz"]}"#,
+            ),
+            (
+                r#"{"baz":"qux","foo":"bar"}"#,
+                r#"[{"op":"remove","path":"/baz"}]"#,
+                r#"{"foo":"bar"}"#,
+            ),
+            (
+                r#"{"foo":["bar","qux","baz"]}"#,
+                r#"[{"op":"remove","path":"/foo/1"}]"#,
+                r#"{"foo":["bar","baz"]}"#,
+            ),
+            (
+                r#"{"baz":"qux","foo":"bar"}"#,
+                r#"[{"op":"replace","path":"/baz","value":"boo"}]"#,
+                r#"{"baz":"boo","foo":"bar"}"#,
+            ),
+            (
+                r#"{"foo":{"bar":"baz","waldo":"fred"},"qux":{"corge":"grault"}}"#,
+                r#"[{"op":"move","from":"/foo/waldo","path":"/qux/thud"}]"#,
+                r#"{"foo":{"bar":"baz"},"qux":{"corge":"grault","thud":"fred"}}"#,
+            ),
+            (
+                r#"{"foo":["all","grass","cows","eat"]}"#,
+                r#"[{"op":"move","from":"/foo/1","path":"/foo/3"}]"#,
+                r#"{"foo":["all","cows","eat","grass"]}"#,
+            ),
+            (
+                r#"{"baz":"qux","foo":["a",2,"c"]}"#,
+                r#"[{"op":"test","path":"/baz","value":"qux"},{"op":"test","path":"/foo/1","value":2}]"#,
+                r#"{"baz":"qux","foo":["a",2,"c"]}"#,
+            ),
+            (
+                r#"{"foo":"bar"}"#,
+                r#"[{"op":"add","path":"/child","value":{"grandchild":{}}}]"#,
+                r#"{"foo":"bar","child":{"grandchild":{}}}"#,
+            ),
+            (
+                r#"{"/":9,"~1":10}"#,
+                r#"[{"op":"test","path":"/~01","value":10}]"#,
+                r#"{"/":9,"~1":10}"#,
+            ),
+            (
+                r#"{"foo":["bar"]}"#,
+                r#"[{"op":"add","path":"/foo/-","value":["abc","def"]}]"#,
+                r#"{"foo":["bar",["abc","def"]]}"#,
+            ),
+        ] {
+            assert_eq!(
+                apply(doc, patch),
+                Ok(parse_complete(result).unwrap()),
+                "{patch}"
+            );
+        }
+    }
+
+    #[test]
+    fn json_patch_extras() {
+        assert_eq!(
+            apply(
+                r#"{"a":[1],"b":1.0}"#,
+                r#"[
+                    {"op":"copy","from":"/a","path":"/c"},
+                    {"op":"add","path":"/c/0","value":0},
+                    {"op":"test","path":"/b","value":1},
+                    {"op":"replace","path":"","value":{"whole":true}}
+                ]"#
+            ),
+            Ok(parse_complete(r#"{"whole":true}"#).unwrap())
+        );
+    }
+
+    #[test]
+    fn json_patch_errors() {
+        for (doc, patch, error) in [
+            (
+                r#"{"baz":"qux"}"#,
+                r#"[{"op":"test","path":"/baz","value":"bar"}]"#,
+                "patch operation 0 failed: test at \"/baz\" expected \"bar\", found \"qux\"",
+            ),
+            (
+                r#"{"/":9,"~1":10}"#,
+                r#"[{"op":"test","path":"/~01","value":"10"}]"#,
+                "patch operation 0 failed: test at \"/~01\" expected \"10\", found 10",
+            ),
+            (
+                r#"{"foo":"bar"}"#,
+                r#"[{"op":"add","path":"/baz/bat","value":"qux"}]"#,
+                "patch operation 0 failed: path \"/baz/bat\" not found",
+            ),
+            (
+                r#"[1]"#,
+                r#"[{"op":"test","path":"","value":[1]},{"op":"add","path":"/2","value":0}]"#,
+                "patch operation 1 failed: path \"/2\" not found",
+            ),
+            (
+                r#"{"a":{}}"#,
+                r#"[{"op":"move","from":"/a","path":"/a/b"}]"#,
+                "patch operation 0 failed: cannot move \"/a\" into its child \"/a/b\"",
+            ),
+            (
+                "{}",
+                r#"{"op":"remove","path":"/a"}"#,
+                "invalid patch: patch is not an array",
+            ),
+            (
+                "{}",
+                r#"[{"op":"remove","path":"/a"},{"op":"add","path":"/a"}]"#,
+                "invalid patch operation 1: missing \"value\"",
+            ),
+            (
+                "{}",
+                r#"[{"op":"frobnicate","path":""}]"#,
+                "invalid patch operation 0: unknown \"op\"",
+            ),
+        ] {
+            assert_eq!(apply(doc, patch).unwrap_err().to_string(), error, "{patch}");
+        }
+    }
+
+    #[test]
+    fn json_patch_is_atomic() {
+        let mut value = parse_complete(r#"{"a":1}"#).unwrap();
+        let ops = [
+            PatchOp::Remove { path: "/a".into() },
+            PatchOp::Remove { path: "/a".into() },
+        ];
+        assert!(value.apply_patch(&ops).is_err());
+        assert_eq!(value, parse_complete(r#"{"a":1}"#).unwrap());
+    }
+
+    #[test]
+    fn diff() {
+        let from =
+            parse_complete(r#"{"a": 1, "b": [1, 2, 3], "c/~": {"d": true}, "e": null}"#).unwrap();
+        let to = parse_complete(r#"{"a": 1, "b": [1, 5], "c/~": {"d": false}, "f": []}"#).unwrap();
+
+        let mut ops = from.diff(&to);
+        // Object keys are visited in map order, which may not be input order.
+        ops.sort_by_key(|op| format!("{op:?}"));
+        assert_eq!(
+            ops,
+            [
+                PatchOp::Add {
+                    path: "/f".into(),
+                    value: Value::Array(vec![])
+                },
+                PatchOp::Remove {
+                    path: "/b/2".into()
+                },
+                PatchOp::Remove { path: "/e".into() },
+                PatchOp::Replace {
+                    path: "/b/1".into(),
+                    value: Value::Integer(5)
+                },
+                PatchOp::Replace {
+                    path: "/c~1~0/d".into(),
+                    value: Value::Bool(false)
+                },
+            ]
+        );
+        assert!(from.diff(&from).is_empty());
+        assert_eq!(
+            from.diff(&Value::Null),
+            [PatchOp::Replace {
+                path: "".into(),
+                value: Value::Null
+            }]
+        );
+    }
+
+    /// Advances the xorshift state `seed` and returns a number below `n`.
+    fn random(seed: &mut u64, n: u64) -> u64 {
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 7;
+        *seed ^= *seed << 17;
+        *seed % n
+    }
+
+    /// Generates a small random value, nested at most `depth` levels deep.
+    fn random_value(seed: &mut u64, depth: u32) -> Value<'static> {
+        match random(seed, if depth == 0 { 4 } else { 6 }) {
+            0 => Value::Null,
+            1 => Value::Bool(random(seed, 2) == 0),
+            2 => Value::Integer(random(seed, 4) as i64),
+            3 => Value::String(["a", "b", "~", "/"][random(seed, 4) as usize].into()),
+            4 => (0..random(seed, 4))
+                .map(|_| random_value(seed, depth - 1))
+                .collect(),
+            _ => Value::Object(
+                (0..random(seed, 4))
+                    .map(|_| {
+                        let key = ["x", "y", "a/b", "m~n"][random(seed, 4) as usize];
+                        (key.into(), random_value(seed, depth - 1))
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    #[test]
+    fn diff_round_trips() {
+        let mut seed = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..500 {
+            let from = random_value(&mut seed, 3);
+            let to = random_value(&mut seed, 3);
+
+            let mut patched = from.clone();
+            assert_eq!(patched.apply_patch(&from.diff(&to)), Ok(()));
+            assert_eq!(patched, to, "{from} -> {to}");
+        }
+    }
+
+    #[test]
+    fn differences() {
+        let old = parse_complete(
+            r#"{"name": "a", "tags": ["x", "y", "z"], "meta": {"v": 1, "gone": true},
+                "list": [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10], "same": [1]}"#,
+        )
+        .unwrap();
+        let new = parse_complete(
+            r#"{"name": ["a"], "tags": ["x", "Y"], "meta": {"v": 1.0, "a/b": null},
+                "list": [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11], "same": [1]}"#,
+        )
+        .unwrap();
+
+        let differences = old.differences(&new);
+        let summary: Vec<_> = differences
+            .iter()
+            .map(|d| {
+                (
+                    d.path.as_str(),
+                    d.old.map(Value::to_json_string),
+                    d.new.map(Value::to_json_string),
+                )
+            })
+            .collect();
+        let some = |s: &str| Some(s.to_owned());
+        assert_eq!(
+            summary,
+            [
+                ("/list/11", None, some("11")),
+                ("/meta/a~1b", None, some("null")),
+                ("/meta/gone", some("true"), None),
+                ("/meta/v", some("1"), some("1.0")),
+                ("/name", some("\"a\""), some("[\"a\"]")),
+                ("/tags/1", some("\"y\""), some("\"Y\"")),
+                ("/tags/2", some("\"z\""), None),
+            ]
+        );
+        assert_eq!(differences[0].new, Some(&new["list"][11]));
+
+        assert!(old.differences(&old.clone()).is_empty());
+        assert_eq!(
+            Value::Null.differences(&Value::Bool(true)),
+            [Difference {
+                path: String::new(),
+                old: Some(&Value::Null),
+                new: Some(&Value::Bool(true)),
+            }]
+        );
+    }
+
+    #[test]
+    fn merge_patch_rfc_examples() {
+        // RFC 7396, appendix A.
+        for (target, patch, result) in [
+            (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+            (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+            (r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#),
+            (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+            (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+            (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+            (
+                r#"{"a":{"b":"c"}}"#,
+                r#"{"a":{"b":"d","c":null}}"#,
+                r#"{"a":{"b":"d"}}"#,
+            ),
+            (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+            (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+            (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+            (r#"{"a":"foo"}"#, r#"null"#, r#"null"#),
+            (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+            (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+            (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+            (
+                r#"{}"#,
+                r#"{"a":{"bb":{"ccc":null}}}"#,
+                r#"{"a":{"bb":{}}}"#,
+            ),
+        ] {
+            let mut value = parse_complete(target).unwrap();
+            value.merge_patch(&parse_complete(patch).unwrap());
+            assert_eq!(value, parse_complete(result).unwrap(), "{target} + {patch}");
+        }
+    }
+
+    #[test]
+    fn merge_patch_edge_cases() {
+        let mut value = parse_complete("3").unwrap();
+        value.merge_patch(&parse_complete(r#"{"a": {"b": null, "c": 1}}"#).unwrap());
+        assert_eq!(value, parse_complete(r#"{"a": {"c": 1}}"#).unwrap());
+
+        let mut value = parse_complete(r#"{"a": 1}"#).unwrap();
+        value.merge_patch(&parse_complete(r#"{"missing": null}"#).unwrap());
+        assert_eq!(value, parse_complete(r#"{"a": 1}"#).unwrap());
+
+        value.merge_patch(&parse_complete("{}").unwrap());
+        assert_eq!(value, parse_complete(r#"{"a": 1}"#).unwrap());
+    }
+
+    #[test]
+    fn merge() {
+        let base = parse_complete(
+            r#"{"server": {"host": "localhost", "port": 80, "tls": {"on": false}},
+                "plugins": ["a"], "name": "base", "drop": {"x": 1}}"#,
+        )
+        .unwrap();
+        let overlay = parse_complete(
+            r#"{"server": {"port": 8080, "tls": {"cert": "c.pem"}},
+                "plugins": ["b", "c"], "name": ["over"], "drop": null, "new": 1}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Value::merge(base, overlay),
+            parse_complete(
+                r#"{"server": {"host": "localhost", "port": 8080,
+                               "tls": {"on": false, "cert": "c.pem"}},
+                    "plugins": ["a", "b", "c"], "name": ["over"], "drop": null, "new": 1}"#
+            )
+            .unwrap()
+        );
+
+        assert_eq!(
+            Value::merge(Value::Array(vec![]), Value::Integer(1)),
+            Value::Integer(1)
+        );
+        assert_eq!(
+            Value::merge(Value::Integer(1), Value::Array(vec![])),
+            Value::Array(vec![])
+        );
+    }
+}
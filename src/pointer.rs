@@ -0,0 +1,167 @@
+use std::borrow::Cow;
+
+use crate::Value;
+
+impl<'a> Value<'a> {
+    /// Looks up a value by [RFC 6901](https://www.rfc-editor.org/rfc/rfc6901)
+    /// JSON Pointer, such as `/foo/bar/0`.
+    ///
+    /// Each `/`-separated segment names an object key, with `~1` standing
+    /// for `/` and `~0` for `~`, or an array index. The empty pointer refers
+    /// to `self`. Returns `None` if the pointer is malformed or any segment
+    /// doesn't exist.
+    pub fn pointer(&self, ptr: &str) -> Option<&Value<'a>> {
+        segments(ptr)?.try_fold(self, |value, segment| match value {
+            Self::Object(map) => map.get(&segment?),
+            Self::Array(arr) => arr.get(array_index(&segment?)?),
+            _ => None,
+        })
+    }
+
+    /// Like [`Value::pointer`], but returns a mutable reference.
+    pub fn pointer_mut(&mut self, ptr: &str) -> Option<&mut Value<'a>> {
+        segments(ptr)?.try_fold(self, |value, segment| match value {
+            Self::Object(map) => map.get_mut(&segment?),
+            Self::Array(arr) => arr.get_mut(array_index(&segment?)?),
+            _ => None,
+        })
+    }
+
+    /// Like [`Value::pointer`], but consumes `self` and returns the value it
+    /// points to.
+    pub fn pointer_value(self, ptr: &str) -> Option<Value<'a>> {
+        segments(ptr)?.try_fold(self, |value, segment| match value {
+            Self::Object(mut map) => map.remove(&segment?),
+            Self::Array(mut arr) => {
+                let index = array_index(&segment?)?;
+                (index < arr.len()).then(|| arr.swap_remove(index))
+            }
+            _ => None,
+        })
+    }
+}
+
+/// Splits `ptr` into its decoded reference tokens. Yields `None` for tokens
+/// with an invalid `~` escape.
+fn segments(ptr: &str) -> Option<impl Iterator<Item = Option<Cow<'_, str>>>> {
+    let tokens = match ptr.strip_prefix('/') {
+        Some(rest) => Some(rest.split('/')),
+        // The empty pointer has no segments.
+        None if ptr.is_empty() => None,
+        None => return None,
+    };
+    Some(tokens.into_iter().flatten().map(decode))
+}
+
+/// Decodes the `~1` and `~0` escapes of a reference token.
+pub(crate) fn decode(token: &str) -> Option<Cow<'_, str>> {
+    if !token.contains('~') {
+        return Some(Cow::Borrowed(token));
+    }
+
+    let mut decoded = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '~' => match chars.next() {
+                Some('0') => decoded.push('~'),
+                Some('1') => decoded.push('/'),
+                _ => return None,
+            },
+            c => decoded.push(c),
+        }
+    }
+    Some(Cow::Owned(decoded))
+}
+
+/// Parses an array index, which must be `0` or have no leading zeros.
+pub(crate) fn array_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || !token.bytes().all(|b| b.is_ascii_digit())
+        || (token.len() > 1 && token.starts_with('0'))
+    {
+        return None;
+    }
+    token.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_complete;
+
+    /// The example document from RFC 6901, section 5.
+    const RFC_EXAMPLE: &str = r#"{
+        "foo": ["bar", "baz"],
+        "": 0,
+        "a/b": 1,
+        "c%d": 2,
+        "e^f": 3,
+        "g|h": 4,
+        "i\\j": 5,
+        "k\"l": 6,
+        " ": 7,
+        "m~n": 8
+    }"#;
+
+    #[test]
+    fn rfc_examples() {
+        let doc = parse_complete(RFC_EXAMPLE).unwrap();
+
+        assert_eq!(doc.pointer(""), Some(&doc));
+        assert_eq!(doc.pointer("/foo"), Some(&doc["foo"]));
+        assert_eq!(doc.pointer("/foo/0"), Some(&Value::String("bar".into())));
+        for (ptr, n) in [
+            ("/", 0),
+            ("/a~1b", 1),
+            ("/c%d", 2),
+            ("/e^f", 3),
+            ("/g|h", 4),
+            ("/i\\j", 5),
+            ("/k\"l", 6),
+            ("/ ", 7),
+            ("/m~0n", 8),
+        ] {
+            assert_eq!(doc.pointer(ptr), Some(&Value::Integer(n)), "{ptr}");
+        }
+    }
+
+    #[test]
+    fn nested_lookup() {
+        let doc = parse_complete(r#"{"users": [{"name": "ada", "tags": {"a/b": 1}}]}"#).unwrap();
+        assert_eq!(
+            doc.pointer("/users/0/name"),
+            Some(&Value::String("ada".into()))
+        );
+        assert_eq!(doc.pointer("/users/0/tags/a~1b"), Some(&Value::Integer(1)));
+        assert_eq!(doc.pointer("/users/0/name/0"), None);
+        assert_eq!(doc.pointer("/users/name"), None);
+    }
+
+    #[test]
+    fn missing_or_malformed() {
+        let doc = parse_complete(RFC_EXAMPLE).unwrap();
+
+        for ptr in [
+            "foo", "/bar", "/foo/2", "/foo/-", "/foo/01", "/foo/+1", "/foo/0/x", "/m~2n", "/m~",
+        ] {
+            assert_eq!(doc.pointer(ptr), None, "{ptr}");
+        }
+    }
+
+    #[test]
+    fn pointer_mut_and_value() {
+        let mut doc = parse_complete(r#"{"a": {"b": [10, 20, 30]}}"#).unwrap();
+
+        *doc.pointer_mut("/a/b/1").unwrap() = Value::Null;
+        assert_eq!(doc.pointer("/a/b/1"), Some(&Value::Null));
+        assert_eq!(doc.pointer_mut("/a/c"), None);
+
+        assert_eq!(
+            doc.clone().pointer_value("/a/b/2"),
+            Some(Value::Integer(30))
+        );
+        assert_eq!(doc.clone().pointer_value("/a/b/3"), None);
+        assert_eq!(doc.clone().pointer_value(""), Some(doc));
+    }
+}
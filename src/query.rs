@@ -0,0 +1,301 @@
+use std::borrow::Cow;
+
+use crate::{ParseError, Value};
+
+impl<'a> Value<'a> {
+    /// Selects every node matching a JSONPath expression, in document order.
+    ///
+    /// Supports a subset of [RFC 9535](https://www.rfc-editor.org/rfc/rfc9535):
+    /// the root `$`, child members `.key` and `['key']`, array indices `[0]`
+    /// (negative ones count from the end), wildcards `.*` and `[*]`, and
+    /// descendants `..key`, `..*` and `..[0]`. For example
+    /// `$.store.book[*].author` or `$..price`.
+    ///
+    /// Fails if `path` is not a valid expression, with the error's offset
+    /// pointing into `path`.
+    pub fn query(&self, path: &str) -> Result<Vec<&Value<'a>>, ParseError> {
+        let segments = parse_jsonpath(path)?;
+        let mut nodes = vec![self];
+
+        for segment in &segments {
+            let mut selected = Vec::new();
+            for node in nodes {
+                match segment {
+                    Segment::Child(selector) => selector.select(node, &mut selected),
+                    Segment::Descendants(selector) => {
+                        select_descendants(node, selector, &mut selected)
+                    }
+                }
+            }
+            nodes = selected;
+        }
+
+        Ok(nodes)
+    }
+}
+
+enum Segment<'p> {
+    Child(Selector<'p>),
+    /// Applies the selector to a node and everything nested inside it.
+    Descendants(Selector<'p>),
+}
+
+enum Selector<'p> {
+    Name(Cow<'p, str>),
+    Index(i64),
+    Wildcard,
+}
+
+impl Selector<'_> {
+    fn select<'v, 'a>(&self, node: &'v Value<'a>, out: &mut Vec<&'v Value<'a>>) {
+        match (self, node) {
+            (Self::Name(name), Value::Object(map)) => out.extend(map.get(name)),
+            (Self::Index(index), Value::Array(arr)) => {
+                let index = if *index < 0 {
+                    usize::try_from(index.unsigned_abs())
+                        .ok()
+                        .and_then(|back| arr.len().checked_sub(back))
+                } else {
+                    usize::try_from(*index).ok()
+                };
+                out.extend(index.and_then(|i| arr.get(i)));
+            }
+            (Self::Wildcard, Value::Array(arr)) => out.extend(arr),
+            (Self::Wildcard, Value::Object(map)) => out.extend(map.values()),
+            _ => {}
+        }
+    }
+}
+
+fn select_descendants<'v, 'a>(
+    node: &'v Value<'a>,
+    selector: &Selector,
+    out: &mut Vec<&'v Value<'a>>,
+) {
+    selector.select(node, out);
+    match node {
+        Value::Array(arr) => arr
+            .iter()
+            .for_each(|child| select_descendants(child, selector, out)),
+        Value::Object(map) => map
+            .values()
+            .for_each(|child| select_descendants(child, selector, out)),
+        _ => {}
+    }
+}
+
+/// Splits a JSONPath expression into its segments.
+fn parse_jsonpath(path: &str) -> Result<Vec<Segment<'_>>, ParseError> {
+    let error = |remaining: &str, message: &str| {
+        ParseError::new(message, path, path.len() - remaining.len())
+    };
+
+    let mut remaining = path
+        .strip_prefix('$')
+        .ok_or_else(|| error(path, "expected '$' at the start of the path"))?;
+    let mut segments = Vec::new();
+
+    while !remaining.is_empty() {
+        let (segment, rest) = if let Some(rest) = remaining.strip_prefix("..") {
+            let (selector, rest) = match rest.strip_prefix('[') {
+                Some(_) => parse_bracket(rest, &error)?,
+                None => parse_shorthand(rest, &error)?,
+            };
+            (Segment::Descendants(selector), rest)
+        } else if let Some(rest) = remaining.strip_prefix('.') {
+            let (selector, rest) = parse_shorthand(rest, &error)?;
+            (Segment::Child(selector), rest)
+        } else if remaining.starts_with('[') {
+            let (selector, rest) = parse_bracket(remaining, &error)?;
+            (Segment::Child(selector), rest)
+        } else {
+            return Err(error(remaining, "expected '.', '..' or '['"));
+        };
+
+        segments.push(segment);
+        remaining = rest;
+    }
+
+    Ok(segments)
+}
+
+type SelectorResult<'p> = Result<(Selector<'p>, &'p str), ParseError>;
+
+/// Parses the `*` or member name after a `.` or `..`.
+fn parse_shorthand<'p>(
+    src: &'p str,
+    error: &impl Fn(&str, &str) -> ParseError,
+) -> SelectorResult<'p> {
+    if let Some(rest) = src.strip_prefix('*') {
+        return Ok((Selector::Wildcard, rest));
+    }
+
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || !c.is_ascii();
+    let end = src.find(|c| !is_name_char(c)).unwrap_or(src.len());
+    let name = &src[..end];
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(error(src, "expected member name or '*'"));
+    }
+    Ok((Selector::Name(Cow::Borrowed(name)), &src[end..]))
+}
+
+/// Parses a `[...]` selector, starting at the `[`.
+fn parse_bracket<'p>(
+    src: &'p str,
+    error: &impl Fn(&str, &str) -> ParseError,
+) -> SelectorResult<'p> {
+    let inner = src[1..].trim_start();
+
+    let (selector, rest) = if let Some(rest) = inner.strip_prefix('*') {
+        (Selector::Wildcard, rest)
+    } else if let Some(quote) = inner.chars().next().filter(|c| matches!(c, '\'' | '"')) {
+        parse_quoted(inner, quote, error)?
+    } else {
+        let sign = usize::from(inner.starts_with('-'));
+        let digits_len = inner[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(inner.len() - sign);
+        let (literal, rest) = inner.split_at(sign + digits_len);
+
+        // RFC 9535 rejects leading zeros and `-0`.
+        let canonical = digits_len == 1 || !literal[sign..].starts_with('0');
+        match literal.parse() {
+            Ok(index) if canonical && literal != "-0" => (Selector::Index(index), rest),
+            _ => return Err(error(inner, "expected index, quoted name or '*'")),
+        }
+    };
+
+    match rest.trim_start().strip_prefix(']') {
+        Some(rest) => Ok((selector, rest)),
+        None => Err(error(rest.trim_start(), "expected ']'")),
+    }
+}
+
+/// Parses a name in single or double quotes, starting at the opening quote.
+fn parse_quoted<'p>(
+    src: &'p str,
+    quote: char,
+    error: &impl Fn(&str, &str) -> ParseError,
+) -> SelectorResult<'p> {
+    let body = &src[1..];
+    let mut name = String::new();
+    let mut chars = body.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c == quote => {
+                let rest = &body[i + 1..];
+                // Borrow when there were no escapes.
+                let name = if name.len() == i {
+                    Cow::Borrowed(&body[..i])
+                } else {
+                    Cow::Owned(name)
+                };
+                return Ok((Selector::Name(name), rest));
+            }
+            '\\' => match chars.next() {
+                Some((_, c @ ('\\' | '\'' | '"' | '/'))) => name.push(c),
+                _ => return Err(error(&body[i..], "invalid escape")),
+            },
+            c => name.push(c),
+        }
+    }
+
+    Err(error(src, "unterminated string"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_complete;
+
+    const STORE: &str = r#"{"store": {
+        "book": [
+            {"author": "Nigel Rees", "price": 8.95},
+            {"author": "Evelyn Waugh", "price": 12.99},
+            {"author": "Herman Melville", "price": 8.99, "isbn": "0-553-21311-3"}
+        ],
+        "bicycle": {"color": "red", "price": 399}
+    }}"#;
+
+    fn strings<'v>(nodes: Vec<&'v Value>) -> Vec<&'v str> {
+        nodes.into_iter().filter_map(Value::as_str).collect()
+    }
+
+    #[test]
+    fn children_and_indices() {
+        let doc = parse_complete(STORE).unwrap();
+
+        assert_eq!(doc.query("$").unwrap(), [&doc]);
+        assert_eq!(
+            strings(doc.query("$.store.book[*].author").unwrap()),
+            ["Nigel Rees", "Evelyn Waugh", "Herman Melville"]
+        );
+        assert_eq!(
+            strings(doc.query("$['store'][\"bicycle\"].color").unwrap()),
+            ["red"]
+        );
+        assert_eq!(
+            strings(doc.query("$.store.book[-1].author").unwrap()),
+            ["Herman Melville"]
+        );
+        assert_eq!(
+            doc.query("$.store.book[0].price").unwrap(),
+            [&Value::Number(8.95)]
+        );
+        assert!(doc.query("$.store.book[3]").unwrap().is_empty());
+        assert!(doc.query("$.store.book[-4]").unwrap().is_empty());
+        assert!(doc.query("$.missing.author").unwrap().is_empty());
+    }
+
+    #[test]
+    fn descendants() {
+        let doc = parse_complete(STORE).unwrap();
+
+        let mut prices: Vec<_> = doc
+            .query("$..price")
+            .unwrap()
+            .into_iter()
+            .filter_map(Value::as_f64)
+            .collect();
+        prices.sort_by(f64::total_cmp);
+        assert_eq!(prices, [8.95, 8.99, 12.99, 399.0]);
+
+        assert_eq!(strings(doc.query("$..isbn").unwrap()), ["0-553-21311-3"]);
+        assert_eq!(
+            strings(doc.query("$..book[1].author").unwrap()),
+            ["Evelyn Waugh"]
+        );
+        assert_eq!(doc.query("$..[0]").unwrap().len(), 1);
+        // The store, its two members, the three books, their fields and the
+        // bicycle's.
+        assert_eq!(doc.query("$..*").unwrap().len(), 1 + 2 + 3 + 7 + 2);
+    }
+
+    #[test]
+    fn invalid_paths() {
+        let doc = parse_complete(STORE).unwrap();
+
+        for (path, offset, message) in [
+            ("store", 0, "expected '$' at the start of the path"),
+            ("$store", 1, "expected '.', '..' or '['"),
+            ("$.", 2, "expected member name or '*'"),
+            ("$.1a", 2, "expected member name or '*'"),
+            ("$[01]", 2, "expected index, quoted name or '*'"),
+            ("$[-0]", 2, "expected index, quoted name or '*'"),
+            ("$[-]", 2, "expected index, quoted name or '*'"),
+            ("$[x]", 2, "expected index, quoted name or '*'"),
+            ("$[0", 3, "expected ']'"),
+            ("$['a", 2, "unterminated string"),
+            ("$['\\n']", 3, "invalid escape"),
+        ] {
+            let error = doc.query(path).unwrap_err();
+            assert_eq!(
+                (error.byte_offset, error.message.as_str()),
+                (offset, message),
+                "{path}"
+            );
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::io::Read;
+
+use crate::{parse_complete, str_from_utf8, OwnedValue, ParseError};
+
+/// Reads `reader` to the end and parses the contents as a single JSON
+/// document, like [`parse_complete`].
+///
+/// Errors from the reader are returned with [`ErrorKind::Io`](crate::ErrorKind::Io).
+pub fn parse_reader(mut reader: impl Read) -> Result<OwnedValue, ParseError> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf).map_err(ParseError::io)?;
+    parse_buffer(&buf)
+}
+
+/// Like [`parse_reader`], but fails with
+/// [`ErrorKind::InputTooLarge`](crate::ErrorKind::InputTooLarge) instead of
+/// reading more than `max_bytes` bytes.
+pub fn parse_reader_limited(reader: impl Read, max_bytes: usize) -> Result<OwnedValue, ParseError> {
+    let mut buf = Vec::new();
+    // Read one byte past the limit to tell an input of exactly `max_bytes`
+    // apart from a longer one.
+    reader
+        .take((max_bytes as u64).saturating_add(1))
+        .read_to_end(&mut buf)
+        .map_err(ParseError::io)?;
+
+    if buf.len() > max_bytes {
+        return Err(ParseError::input_too_large(max_bytes));
+    }
+
+    parse_buffer(&buf)
+}
+
+fn parse_buffer(buf: &[u8]) -> Result<OwnedValue, ParseError> {
+    Ok(parse_complete(str_from_utf8(buf)?)?.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+    use crate::ErrorKind;
+
+    #[test]
+    fn reads_whole_input() {
+        let value = parse_reader(&b"{\"a\": [1, \"\\u00e9\"]}\n"[..]).unwrap();
+        assert_eq!(
+            value,
+            OwnedValue::Object(
+                [(
+                    "a".to_owned(),
+                    OwnedValue::Array(vec![
+                        OwnedValue::Integer(1),
+                        OwnedValue::String("\u{e9}".to_owned())
+                    ])
+                )]
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn characters_split_across_reads() {
+        /// Hands out one byte per `read`, splitting every multi-byte character.
+        struct ByteByByte<'a>(&'a [u8]);
+
+        impl Read for ByteByByte<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let Some((first, rest)) = self.0.split_first() else {
+                    return Ok(0);
+                };
+                buf[0] = *first;
+                self.0 = rest;
+                Ok(1)
+            }
+        }
+
+        let src = "[\"h\u{e9}llo \u{1f600}\", \"\u{4e2d}\"]";
+        assert_eq!(
+            parse_reader(ByteByByte(src.as_bytes())).unwrap(),
+            src.parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn limit() {
+        assert_eq!(
+            parse_reader_limited(&b"[1, 2]"[..], 6).unwrap(),
+            OwnedValue::Array(vec![OwnedValue::Integer(1), OwnedValue::Integer(2)])
+        );
+
+        let error = parse_reader_limited(&b"[1, 2] "[..], 6).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InputTooLarge);
+        assert_eq!(
+            error.to_string(),
+            "parse error: input is larger than 6 bytes"
+        );
+    }
+
+    #[test]
+    fn reader_errors() {
+        struct Failing;
+
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "pipe closed"))
+            }
+        }
+
+        let error = parse_reader(Failing).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Io(io::ErrorKind::BrokenPipe));
+        assert_eq!(error.to_string(), "I/O error: pipe closed");
+
+        let error = parse_reader(&b"[1] x"[..]).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Syntax);
+        assert_eq!(error.byte_offset, 4);
+    }
+}
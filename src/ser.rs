@@ -0,0 +1,586 @@
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+
+use crate::value::parse_raw_number;
+use crate::{Value, WriteConfig};
+
+impl Value<'_> {
+    /// Returns `self` as compact JSON that parses back to an equal value.
+    ///
+    /// This is the same output as [`Value::to_compact_string`].
+    pub fn to_json_string(&self) -> String {
+        self.to_compact_string()
+    }
+
+    /// Returns `self` as JSON without any insignificant whitespace, e.g.
+    /// `{"a":1,"b":[true,null]}`.
+    pub fn to_compact_string(&self) -> String {
+        self.write_to_string(Style::Compact)
+    }
+
+    /// Returns `self` as indented JSON with `indent` spaces per nesting
+    /// level, see [`Value::to_json_pretty`].
+    pub fn to_pretty_string(&self, indent: usize) -> String {
+        self.to_json_pretty(&" ".repeat(indent))
+    }
+
+    /// Returns `self` as indented JSON, one array element or object entry
+    /// per line, prefixed by `indent` once per nesting level. Closing
+    /// brackets go on their own line at the parent's level.
+    ///
+    /// An empty `indent` gives the same output as [`Value::to_json_string`].
+    pub fn to_json_pretty(&self, indent: &str) -> String {
+        if indent.is_empty() {
+            return self.to_json_string();
+        }
+        self.write_to_string(Style::Pretty(indent))
+    }
+
+    /// Returns `self` in a canonical form for hashing, signing or
+    /// deduplication: compact like [`Value::to_compact_string`], with the
+    /// keys of every object sorted by their UTF-8 bytes.
+    ///
+    /// Values that compare equal give the same string, so `-0.0` is written
+    /// as `0.0`. Numbers otherwise use the shortest digits that parse back
+    /// to the same `f64`, in exponential notation for magnitudes from 1e21
+    /// up and below 1e-6, as in `1e300` and `1e-7`.
+    pub fn to_canonical_string(&self) -> String {
+        self.write_to_string(Style::Canonical)
+    }
+
+    /// Returns `self` as canonical JSON following the JSON Canonicalization
+    /// Scheme of [RFC 8785](https://www.rfc-editor.org/rfc/rfc8785), for
+    /// hashes and signatures that other JCS implementations can check.
+    ///
+    /// Unlike [`Value::to_canonical_string`], keys are sorted by their
+    /// UTF-16 code units and numbers are formatted like JavaScript does, so
+    /// `1.0` is written as `1` and `1e21` as `1e+21`. As JCS treats every
+    /// number as an `f64`, integers beyond 2^53 are rounded to one. Strings
+    /// are written as they are, without Unicode normalization, and
+    /// non-finite numbers are written as `null`.
+    pub fn to_canonical_json(&self) -> String {
+        self.write_to_string(Style::Jcs)
+    }
+
+    /// Returns `self` as JSON printed according to `config`.
+    pub fn to_string_with(&self, config: &WriteConfig) -> String {
+        let style = match config.indent.as_str() {
+            "" => Style::Compact,
+            indent => Style::Pretty(indent),
+        };
+        if !config.ascii_only {
+            return self.write_to_string(style);
+        }
+
+        let mut out = String::new();
+        // Writing into a `String` cannot fail.
+        let _ = write_value(&mut AsciiOnly(&mut out), self, style, 0);
+        out
+    }
+
+    fn write_to_string(&self, style: Style) -> String {
+        let mut out = String::new();
+        // Writing into a `String` cannot fail.
+        let _ = write_value(&mut out, self, style, 0);
+        out
+    }
+}
+
+/// Writes `value` as compact JSON, the same as [`Value::to_json_string`].
+///
+/// Use [`Pretty`] for indented output.
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(f, self, Style::Compact, 0)
+    }
+}
+
+/// Displays a [`Value`] as indented JSON, two spaces per nesting level, as
+/// by [`Value::to_pretty_string`].
+#[derive(Debug, Clone, Copy)]
+pub struct Pretty<'v, 'a>(pub &'v Value<'a>);
+
+impl fmt::Display for Pretty<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(f, self.0, Style::Pretty("  "), 0)
+    }
+}
+
+/// Whitespace written between tokens.
+#[derive(Clone, Copy)]
+enum Style<'i> {
+    Compact,
+    /// Compact, with sorted keys and `-0.0` written as `0.0`.
+    Canonical,
+    /// Compact, as specified by RFC 8785.
+    Jcs,
+    /// One element per line, indented by the given string per level.
+    Pretty(&'i str),
+}
+
+impl Style<'_> {
+    fn colon(self) -> &'static str {
+        match self {
+            Self::Compact | Self::Canonical | Self::Jcs => ":",
+            Self::Pretty(_) => ": ",
+        }
+    }
+
+    /// Writes whatever goes between an opening bracket or a `,` and the next
+    /// element at `depth`.
+    fn write_separator(self, f: &mut impl Write, first: bool, depth: usize) -> fmt::Result {
+        if !first {
+            f.write_char(',')?;
+        }
+        match self {
+            Self::Compact | Self::Canonical | Self::Jcs => Ok(()),
+            Self::Pretty(indent) => write_line(f, indent, depth),
+        }
+    }
+
+    /// Writes whatever goes before the closing bracket of a non-empty
+    /// container at `depth`.
+    fn write_close(self, f: &mut impl Write, depth: usize) -> fmt::Result {
+        match self {
+            Self::Pretty(indent) => write_line(f, indent, depth),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Escapes everything written to it that isn't ASCII as `\uXXXX`.
+///
+/// Outside of strings, JSON is ASCII, so this only ever escapes characters
+/// within strings.
+struct AsciiOnly<W>(W);
+
+impl<W: Write> Write for AsciiOnly<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut rest = s;
+        while let Some(i) = rest.find(|c: char| !c.is_ascii()) {
+            self.0.write_str(&rest[..i])?;
+            let c = rest[i..].chars().next().unwrap_or_default();
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                write!(self.0, "\\u{unit:04x}")?;
+            }
+            rest = &rest[i + c.len_utf8()..];
+        }
+        self.0.write_str(rest)
+    }
+}
+
+fn write_line(f: &mut impl Write, indent: &str, depth: usize) -> fmt::Result {
+    f.write_char('\n')?;
+    for _ in 0..depth {
+        f.write_str(indent)?;
+    }
+    Ok(())
+}
+
+fn write_value(f: &mut impl Write, value: &Value, style: Style, depth: usize) -> fmt::Result {
+    match value {
+        Value::Null => f.write_str("null"),
+        Value::Bool(b) => write!(f, "{b}"),
+        Value::String(str) => write_escaped(f, str),
+        // Adding 0.0 turns -0.0 into 0.0 and leaves everything else alone.
+        Value::Number(num) if matches!(style, Style::Canonical) => write_float(f, num + 0.0),
+        Value::Number(num) if matches!(style, Style::Jcs) => write_es_number(f, *num),
+        Value::Number(num) => write_float(f, *num),
+        Value::Integer(num) if matches!(style, Style::Jcs) => write_es_number(f, *num as f64),
+        Value::Integer(num) => write!(f, "{num}"),
+        Value::BigInteger(num) if matches!(style, Style::Jcs) => write_es_number(f, *num as f64),
+        Value::BigInteger(num) => write!(f, "{num}"),
+        Value::RawNumber(raw) if matches!(style, Style::Canonical | Style::Jcs) => {
+            write_value(f, &parse_raw_number(raw), style, depth)
+        }
+        Value::RawNumber(raw) => f.write_str(raw),
+        Value::Array(arr) => {
+            f.write_char('[')?;
+            for (i, e) in arr.iter().enumerate() {
+                style.write_separator(f, i == 0, depth + 1)?;
+                write_value(f, e, style, depth + 1)?;
+            }
+            if !arr.is_empty() {
+                style.write_close(f, depth)?;
+            }
+            f.write_char(']')
+        }
+        Value::Object(values) if matches!(style, Style::Canonical) => {
+            let mut entries: Vec<_> = values.iter().collect();
+            entries.sort_unstable_by_key(|&(k, _)| k);
+            write_object(f, entries, style, depth)
+        }
+        Value::Object(values) if matches!(style, Style::Jcs) => {
+            let mut entries: Vec<_> = values.iter().collect();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            write_object(f, entries, style, depth)
+        }
+        Value::Object(values) => write_object(f, values, style, depth),
+    }
+}
+
+fn write_object<'v, 'a: 'v>(
+    f: &mut impl Write,
+    entries: impl IntoIterator<Item = (&'v Cow<'a, str>, &'v Value<'a>)>,
+    style: Style,
+    depth: usize,
+) -> fmt::Result {
+    f.write_char('{')?;
+    let mut empty = true;
+    for (k, v) in entries {
+        style.write_separator(f, empty, depth + 1)?;
+        empty = false;
+        write_escaped(f, k)?;
+        f.write_str(style.colon())?;
+        write_value(f, v, style, depth + 1)?;
+    }
+    if !empty {
+        style.write_close(f, depth)?;
+    }
+    f.write_char('}')
+}
+
+/// Writes a [`Value::Number`], keeping a `.0` on integral values so they do
+/// not parse back as [`Value::Integer`]. Like [`write_es_number`], magnitudes
+/// from 1e21 up and below 1e-6 use exponential notation, as in `1e300`.
+fn write_float(f: &mut impl Write, num: f64) -> fmt::Result {
+    if !num.is_finite() {
+        return f.write_str("null");
+    }
+    if num != 0.0 && !(1e-6..1e21).contains(&num.abs()) {
+        return write!(f, "{num:e}");
+    }
+
+    let text = num.to_string();
+    f.write_str(&text)?;
+    if text.bytes().all(|b| b == b'-' || b.is_ascii_digit()) {
+        f.write_str(".0")?;
+    }
+    Ok(())
+}
+
+/// Writes `num` the way JavaScript's `Number.prototype.toString` does, as
+/// required by RFC 8785: the shortest digits that round-trip, in plain
+/// notation for magnitudes from 1e-6 up to 1e21 and in exponential notation
+/// otherwise.
+fn write_es_number(f: &mut impl Write, num: f64) -> fmt::Result {
+    if !num.is_finite() {
+        return f.write_str("null");
+    }
+    if num == 0.0 {
+        return f.write_char('0');
+    }
+    if num < 0.0 {
+        f.write_char('-')?;
+    }
+
+    // `{:e}` gives the shortest round-tripping digits, as in `1.2345e-7`.
+    let scientific = format!("{:e}", num.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    // The decimal point goes after the first `n` digits.
+    let n = exponent.parse::<i32>().unwrap_or_default() + 1;
+
+    if (k..=21).contains(&n) {
+        f.write_str(&digits)?;
+        for _ in k..n {
+            f.write_char('0')?;
+        }
+        Ok(())
+    } else if (1..=21).contains(&n) {
+        let (int, frac) = digits.split_at(n as usize);
+        write!(f, "{int}.{frac}")
+    } else if (-5..=0).contains(&n) {
+        f.write_str("0.")?;
+        for _ in n..0 {
+            f.write_char('0')?;
+        }
+        f.write_str(&digits)
+    } else {
+        let (first, rest) = digits.split_at(1);
+        f.write_str(first)?;
+        if !rest.is_empty() {
+            write!(f, ".{rest}")?;
+        }
+        let sign = if n > 0 { "+" } else { "-" };
+        write!(f, "e{sign}{}", (n - 1).abs())
+    }
+}
+
+/// Writes `s` as a quoted JSON string, escaping `"`, `\` and control
+/// characters.
+fn write_escaped(f: &mut impl Write, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '\u{08}' => f.write_str("\\b")?,
+            '\u{0c}' => f.write_str("\\f")?,
+            c if c < '\u{20}' => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{parse_complete, Map};
+
+    #[test]
+    fn display_scalars() {
+        assert_eq!(Value::Null.to_string(), "null");
+        assert_eq!(Value::Bool(true).to_string(), "true");
+        assert_eq!(Value::Number(-1.5).to_string(), "-1.5");
+        assert_eq!(Value::Number(2.0).to_string(), "2.0");
+        assert_eq!(Value::Integer(-42).to_string(), "-42");
+        assert_eq!(Value::Number(f64::NAN).to_string(), "null");
+        assert_eq!(
+            Value::String("a\"b\\c\n\u{1}".into()).to_string(),
+            "\"a\\\"b\\\\c\\n\\u0001\""
+        );
+    }
+
+    #[test]
+    fn display_containers() {
+        let value = Value::Array(vec![
+            Value::Integer(1),
+            Value::Object(Map::from([("k".into(), Value::Array(vec![]))])),
+        ]);
+        assert_eq!(value.to_string(), "[1,{\"k\":[]}]");
+        assert_eq!(value.to_string(), value.to_json_string());
+        assert_eq!(Pretty(&value).to_string(), value.to_pretty_string(2));
+    }
+
+    #[test]
+    fn compact() {
+        let value = Value::Object(Map::from([(
+            "a\"".into(),
+            Value::Array(vec![
+                Value::Integer(123),
+                Value::Bool(true),
+                Value::Null,
+                Value::Number(0.5),
+            ]),
+        )]));
+        assert_eq!(value.to_compact_string(), "{\"a\\\"\":[123,true,null,0.5]}");
+    }
+
+    #[test]
+    fn pretty() {
+        let value = Value::Object(Map::from([(
+            "a".into(),
+            Value::Array(vec![
+                Value::Integer(1),
+                Value::Object(Map::from([("b".into(), Value::Array(vec![Value::Null]))])),
+                Value::Array(vec![]),
+                Value::Object(Map::new()),
+            ]),
+        )]));
+
+        assert_eq!(
+            value.to_pretty_string(2),
+            "{\n  \"a\": [\n    1,\n    {\n      \"b\": [\n        null\n      ]\n    },\n    [],\n    {}\n  ]\n}"
+        );
+        assert_eq!(
+            value.to_pretty_string(1),
+            "{\n \"a\": [\n  1,\n  {\n   \"b\": [\n    null\n   ]\n  },\n  [],\n  {}\n ]\n}"
+        );
+        assert_eq!(value.to_pretty_string(0), value.to_compact_string());
+        assert_eq!(
+            value.to_json_pretty("\t"),
+            "{\n\t\"a\": [\n\t\t1,\n\t\t{\n\t\t\t\"b\": [\n\t\t\t\tnull\n\t\t\t]\n\t\t},\n\t\t[],\n\t\t{}\n\t]\n}"
+        );
+        assert_eq!(value.to_json_pretty(""), value.to_json_string());
+        assert_eq!(Value::Bool(true).to_pretty_string(4), "true");
+    }
+
+    #[test]
+    fn pretty_round_trips() {
+        let src = "[[[[{\"a\": [[1], {\"b\": {\"c\": [true, false, null]}}]}]]], \"x\"]";
+        let value = parse_complete(src).unwrap();
+        for indent in 0..=8 {
+            assert_eq!(
+                parse_complete(&value.to_pretty_string(indent)).as_ref(),
+                Ok(&value)
+            );
+        }
+    }
+
+    #[test]
+    fn escapes_round_trip() {
+        let value =
+            parse_complete(r#""\" \\ \/ \b \f \n \r \t \u0041 \u00e9 \ud83d\ude00 \u0001""#)
+                .unwrap();
+        assert_eq!(
+            value,
+            Value::String("\" \\ / \u{08} \u{0c} \n \r \t A \u{e9} \u{1f600} \u{1}".into())
+        );
+        assert_eq!(parse_complete(&value.to_compact_string()), Ok(value));
+    }
+
+    #[test]
+    fn numbers_keep_their_kind() {
+        assert_eq!(parse_complete("1"), Ok(Value::Integer(1)));
+        assert_eq!(parse_complete("1.0"), Ok(Value::Number(1.0)));
+
+        for src in [
+            "1",
+            "1.0",
+            "9007199254740993",
+            "-9223372036854775808",
+            "1e300",
+        ] {
+            let value = parse_complete(src).unwrap();
+            assert_eq!(parse_complete(&value.to_json_string()), Ok(value));
+        }
+    }
+
+    #[cfg(feature = "preserve-order")]
+    #[test]
+    fn keys_keep_input_order() {
+        let src = r#"{"z":1,"a":{"y":[],"b":null},"m":true}"#;
+        assert_eq!(parse_complete(src).unwrap().to_json_string(), src);
+
+        let value = parse_complete(r#"{"z": {"y": 1, "b": 2}, "a": 3}"#).unwrap();
+        assert_eq!(
+            Pretty(&value).to_string(),
+            "{\n  \"z\": {\n    \"y\": 1,\n    \"b\": 2\n  },\n  \"a\": 3\n}"
+        );
+    }
+
+    #[cfg(any(feature = "preserve-order", feature = "sorted-keys"))]
+    #[test]
+    fn sorted_output() {
+        let mut value = parse_complete(r#"{"z":1,"a":{"y":[{"d":0,"c":0}],"b":null}}"#).unwrap();
+        value.sort_keys();
+        assert_eq!(
+            value.to_json_string(),
+            r#"{"a":{"b":null,"y":[{"c":0,"d":0}]},"z":1}"#
+        );
+    }
+
+    #[test]
+    fn canonical() {
+        let a = parse_complete(r#"{"b": [1, {"y": -0.0, "x": 2.50}], "a": "\u00e9", "B": null}"#)
+            .unwrap();
+        let b =
+            parse_complete(r#"{"B": null, "a": "é", "b": [1, {"x": 25e-1, "y": 0.0}]}"#).unwrap();
+        assert_eq!(a.to_canonical_string(), b.to_canonical_string());
+        assert_eq!(
+            a.to_canonical_string(),
+            r#"{"B":null,"a":"é","b":[1,{"x":2.5,"y":0.0}]}"#
+        );
+        assert_eq!(parse_complete(&a.to_canonical_string()), Ok(a));
+
+        // Sorted by bytes, not characters or case.
+        let value = parse_complete(r#"{"é": 1, "z": 2, "Z": 3, "": 4}"#).unwrap();
+        assert_eq!(value.to_canonical_string(), r#"{"":4,"Z":3,"z":2,"é":1}"#);
+
+        // Large and small magnitudes switch to exponential notation.
+        let value = parse_complete("[1e300, -1e21, 1e20, 1e-7, 2.5e-6, 0.000001]").unwrap();
+        assert_eq!(
+            value.to_canonical_string(),
+            "[1e300,-1e21,100000000000000000000.0,1e-7,0.0000025,0.000001]"
+        );
+        assert_eq!(parse_complete(&value.to_canonical_string()), Ok(value));
+    }
+
+    #[test]
+    fn canonical_json() {
+        // Sorting by UTF-16 code units puts the emoji (a surrogate pair)
+        // before U+FB33, unlike sorting by UTF-8 bytes.
+        let value = parse_complete(
+            r#"{"\u20ac": 1, "\r": 2, "\ufb33": 3, "1": 4, "\ud83d\ude00": 5, "\u0080": 6, "\u00f6": 7}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            value.to_canonical_json(),
+            "{\"\\r\":2,\"1\":4,\"\u{80}\":6,\"\u{f6}\":7,\"\u{20ac}\":1,\"\u{1f600}\":5,\"\u{fb33}\":3}"
+        );
+
+        let a = parse_complete(r#"{"b": [1.0, {"y": -0.0, "x": 2}], "a": "x"}"#).unwrap();
+        let b = parse_complete(r#"{"a": "x", "b": [1, {"x": 2e0, "y": 0}]}"#).unwrap();
+        assert_eq!(a.to_canonical_json(), r#"{"a":"x","b":[1,{"x":2,"y":0}]}"#);
+        assert_eq!(a.to_canonical_json(), b.to_canonical_json());
+    }
+
+    #[test]
+    fn canonical_json_numbers() {
+        // From RFC 8785, appendix B, and around the notation boundaries.
+        for (num, text) in [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (5e-324, "5e-324"),
+            (-5e-324, "-5e-324"),
+            (1.7976931348623157e308, "1.7976931348623157e+308"),
+            (9007199254740992.0, "9007199254740992"),
+            (9007199254740994.0, "9007199254740994"),
+            (295147905179352830000.0, "295147905179352830000"),
+            (1e21, "1e+21"),
+            (1.5e21, "1.5e+21"),
+            (1e20, "100000000000000000000"),
+            (333333333.3333333, "333333333.3333333"),
+            (4.5, "4.5"),
+            (0.002, "0.002"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (-1.25e-7, "-1.25e-7"),
+            (1e23, "1e+23"),
+            (f64::NAN, "null"),
+        ] {
+            assert_eq!(Value::Number(num).to_canonical_json(), text, "{num:e}");
+        }
+        assert_eq!(Value::Integer(-42).to_canonical_json(), "-42");
+        assert_eq!(
+            Value::Integer(9007199254740993).to_canonical_json(),
+            "9007199254740992"
+        );
+    }
+
+    #[test]
+    fn write_config() {
+        let value =
+            parse_complete(r#"{"k": ["caf\u00e9", "\u4e2d\u6587", "\ud83d\ude00!", "a\"b"]}"#)
+                .unwrap();
+        assert_eq!(
+            value.to_string_with(&WriteConfig::new()),
+            value.to_json_string()
+        );
+        assert_eq!(
+            value.to_string_with(&WriteConfig::new().indent("\t")),
+            value.to_json_pretty("\t")
+        );
+
+        let ascii = value.to_string_with(&WriteConfig::new().ascii_only(true));
+        assert_eq!(
+            ascii,
+            r#"{"k":["caf\u00e9","\u4e2d\u6587","\ud83d\ude00!","a\"b"]}"#
+        );
+        assert_eq!(parse_complete(&ascii).as_ref(), Ok(&value));
+
+        let pretty = value.to_string_with(&WriteConfig::new().indent(" ").ascii_only(true));
+        assert!(pretty.is_ascii());
+        assert!(pretty.starts_with("{\n \"k\": [\n  \"caf\\u00e9\","));
+        assert_eq!(parse_complete(&pretty), Ok(value));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let src = "{\"a\": [1, 2.5, -3e-7, 4.0, 9007199254740993], \"b\": {\"c\": null, \"d\": \"te\\\"x\\nt\"}, \"e\": false}";
+        let value = parse_complete(src).unwrap();
+        assert_eq!(parse_complete(&value.to_string()).as_ref(), Ok(&value));
+        assert_eq!(
+            parse_complete(&value.to_compact_string()).as_ref(),
+            Ok(&value)
+        );
+        assert_eq!(parse_complete(&value.to_json_string()), Ok(value));
+    }
+}
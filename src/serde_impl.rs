@@ -0,0 +1,222 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+
+use crate::value::parse_raw_number;
+use crate::{Map, OwnedValue, Value};
+
+impl Serialize for Value<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Number(n) => serializer.serialize_f64(*n),
+            Value::Integer(n) => serializer.serialize_i64(*n),
+            Value::BigInteger(n) => serializer.serialize_i128(*n),
+            Value::RawNumber(raw) => parse_raw_number(raw).serialize(serializer),
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Array(arr) => {
+                let mut seq = serializer.serialize_seq(Some(arr.len()))?;
+                for e in arr {
+                    seq.serialize_element(e)?;
+                }
+                seq.end()
+            }
+            Value::Object(values) => {
+                let mut map = serializer.serialize_map(Some(values.len()))?;
+                for (k, v) in values {
+                    map.serialize_entry(k.as_ref(), v)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl Serialize for OwnedValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.borrow().serialize(serializer)
+    }
+}
+
+/// Borrows strings from the input when the format allows it, and copies
+/// them otherwise.
+impl<'de> Deserialize<'de> for Value<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for OwnedValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(OwnedValue::from)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<Self::Value, E> {
+        Ok(Value::Bool(b))
+    }
+
+    fn visit_i64<E>(self, n: i64) -> Result<Self::Value, E> {
+        Ok(Value::Integer(n))
+    }
+
+    /// Integers beyond `i64::MAX` become a `Number`, or a `BigInteger`
+    /// with the `i128` feature, like they do when parsed.
+    fn visit_u64<E>(self, n: u64) -> Result<Self::Value, E> {
+        #[cfg(feature = "i128")]
+        return Ok(i64::try_from(n).map_or(Value::BigInteger(n.into()), Value::Integer));
+        #[cfg(not(feature = "i128"))]
+        Ok(i64::try_from(n).map_or(Value::Number(n as f64), Value::Integer))
+    }
+
+    #[cfg(feature = "i128")]
+    fn visit_i128<E>(self, n: i128) -> Result<Self::Value, E> {
+        Ok(i64::try_from(n).map_or(Value::BigInteger(n), Value::Integer))
+    }
+
+    fn visit_f64<E>(self, n: f64) -> Result<Self::Value, E> {
+        Ok(Value::Number(n))
+    }
+
+    fn visit_borrowed_str<E>(self, s: &'de str) -> Result<Self::Value, E> {
+        Ok(Value::String(Cow::Borrowed(s)))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Self::Value, E> {
+        Ok(Value::String(Cow::Owned(s.to_owned())))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<Self::Value, E> {
+        Ok(Value::String(Cow::Owned(s)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(e) = seq.next_element()? {
+            elements.push(e);
+        }
+        Ok(Value::Array(elements))
+    }
+
+    /// Later entries win over earlier ones with the same key, as with
+    /// [`DuplicateKeyBehavior::UseLast`](crate::DuplicateKeyBehavior::UseLast).
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = Map::new();
+        while let Some((Key(k), v)) = access.next_entry()? {
+            map.insert(k, v);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+/// An object key, borrowed from the input when possible.
+struct Key<'de>(Cow<'de, str>);
+
+impl<'de> Deserialize<'de> for Key<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(KeyVisitor)
+    }
+}
+
+struct KeyVisitor;
+
+impl<'de> Visitor<'de> for KeyVisitor {
+    type Value = Key<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string key")
+    }
+
+    fn visit_borrowed_str<E>(self, s: &'de str) -> Result<Self::Value, E> {
+        Ok(Key(Cow::Borrowed(s)))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Self::Value, E> {
+        Ok(Key(Cow::Owned(s.to_owned())))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<Self::Value, E> {
+        Ok(Key(Cow::Owned(s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_complete;
+
+    #[test]
+    fn serializes_like_to_json_string() {
+        let src = r#"{"a":[1,2.5,"x\n",null],"b":{"c":true}}"#;
+        let value = parse_complete(src).unwrap();
+        assert_eq!(
+            serde_json::to_string(&value).unwrap().parse::<OwnedValue>(),
+            Ok(value.clone().into_owned())
+        );
+        assert_eq!(
+            serde_json::to_string(&Value::Number(1.0)).unwrap(),
+            Value::Number(1.0).to_json_string()
+        );
+    }
+
+    #[test]
+    fn deserializes_borrowing_strings() {
+        let src = r#"{"key": ["text", 1, -2, 18446744073709551615, 0.5, false, null]}"#;
+        let value: Value = serde_json::from_str(src).unwrap();
+        assert_eq!(value, parse_complete(src).unwrap());
+
+        let Value::Object(map) = &value else {
+            panic!("expected an object");
+        };
+        let (key, array) = map.iter().next().unwrap();
+        assert!(matches!(key, Cow::Borrowed(_)));
+        assert!(matches!(array[0], Value::String(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn converts_to_and_from_serde_json() {
+        let value = parse_complete(r#"{"a": [1, "b"], "c": {}}"#).unwrap();
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json, serde_json::json!({"a": [1, "b"], "c": {}}));
+
+        let owned: OwnedValue = serde_json::from_value(json).unwrap();
+        assert_eq!(owned, value.into_owned());
+    }
+
+    #[test]
+    fn nests_inside_other_serde_types() {
+        use std::collections::BTreeMap;
+
+        let src = r#"{"first": [1, {"a": null}], "second": "text"}"#;
+        let fields: BTreeMap<String, OwnedValue> = serde_json::from_str(src).unwrap();
+        assert_eq!(fields["second"], OwnedValue::String("text".to_owned()));
+
+        let round_tripped: OwnedValue =
+            serde_json::from_str(&serde_json::to_string(&fields).unwrap()).unwrap();
+        assert_eq!(round_tripped, src.parse().unwrap());
+    }
+}
@@ -0,0 +1,208 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::patch::child_path;
+use crate::{strip_bom, Context, Event, Map, ParseConfig, ParseError, Value};
+
+/// Parses `src` as a single JSON document like
+/// [`parse_complete`](crate::parse_complete), also returning where in `src`
+/// each value came from.
+pub fn parse_spanned(src: &str) -> Result<(Value<'_>, Spans), ParseError> {
+    let mut builder = Builder::default();
+    Context::new(src, &ParseConfig::default())
+        .parse_events(strip_bom(src), |event, span| builder.event(event, span))?;
+
+    // A successful parse has always completed the document.
+    let value = builder.value.unwrap_or(Value::Null);
+    Ok((value, builder.spans))
+}
+
+/// The byte ranges in the source of the values of a document, see
+/// [`parse_spanned`].
+///
+/// Values are looked up by JSON Pointer, as for
+/// [`Value::pointer`](crate::Value::pointer), except that the pointer must
+/// be spelled exactly as `~0` and `~1` escapes require.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Spans {
+    values: HashMap<String, Range<usize>>,
+    keys: HashMap<String, Range<usize>>,
+}
+
+impl Spans {
+    /// Returns the byte range of the value at `pointer`. The range of an
+    /// array or object includes its brackets, that of a string its quotes.
+    pub fn get(&self, pointer: &str) -> Option<Range<usize>> {
+        self.values.get(pointer).cloned()
+    }
+
+    /// Returns the byte range of the key, including its quotes, of the
+    /// object entry at `pointer`.
+    pub fn key(&self, pointer: &str) -> Option<Range<usize>> {
+        self.keys.get(pointer).cloned()
+    }
+}
+
+/// An array or object whose elements are still being parsed.
+struct Open<'a> {
+    container: Container<'a>,
+    /// Where its opening bracket is.
+    start: usize,
+    /// The length of its JSON Pointer in `Builder::path`.
+    path_len: usize,
+}
+
+enum Container<'a> {
+    Array(Vec<Value<'a>>),
+    Object {
+        map: Map<Cow<'a, str>, Value<'a>>,
+        /// The key of the entry being parsed.
+        key: Cow<'a, str>,
+    },
+}
+
+/// Builds a [`Value`] and its [`Spans`] from the events of a document.
+#[derive(Default)]
+struct Builder<'a> {
+    stack: Vec<Open<'a>>,
+    /// JSON Pointer to the value being parsed.
+    path: String,
+    spans: Spans,
+    /// The whole document, once it is complete.
+    value: Option<Value<'a>>,
+}
+
+impl<'a> Builder<'a> {
+    fn event(&mut self, event: Event<'a>, span: Range<usize>) {
+        match event {
+            Event::StartArray | Event::StartObject => {
+                self.enter();
+                let container = match event {
+                    Event::StartArray => Container::Array(Vec::new()),
+                    _ => Container::Object {
+                        map: Map::new(),
+                        key: Cow::Borrowed(""),
+                    },
+                };
+                self.stack.push(Open {
+                    container,
+                    start: span.start,
+                    path_len: self.path.len(),
+                });
+            }
+            Event::Key(key) => {
+                if let Some(Open {
+                    container: Container::Object { key: current, .. },
+                    path_len,
+                    ..
+                }) = self.stack.last_mut()
+                {
+                    self.path.truncate(*path_len);
+                    self.path = child_path(&self.path, &key);
+                    self.spans.keys.insert(self.path.clone(), span);
+                    *current = key;
+                }
+            }
+            Event::Value(value) => {
+                self.enter();
+                self.spans.values.insert(self.path.clone(), span);
+                self.add(value);
+            }
+            Event::EndArray | Event::EndObject => {
+                let Some(open) = self.stack.pop() else {
+                    return;
+                };
+                self.path.truncate(open.path_len);
+                self.spans
+                    .values
+                    .insert(self.path.clone(), open.start..span.end);
+                self.add(match open.container {
+                    Container::Array(elements) => Value::Array(elements),
+                    Container::Object { map, .. } => Value::Object(map),
+                });
+            }
+        }
+    }
+
+    /// Points `path` at the value that starts next.
+    fn enter(&mut self) {
+        let Some(open) = self.stack.last() else {
+            self.path.clear();
+            return;
+        };
+
+        self.path.truncate(open.path_len);
+        self.path = match &open.container {
+            Container::Array(elements) => child_path(&self.path, &elements.len().to_string()),
+            Container::Object { key, .. } => child_path(&self.path, key),
+        };
+    }
+
+    /// Hands a complete value to the innermost open container.
+    fn add(&mut self, value: Value<'a>) {
+        match self.stack.last_mut() {
+            Some(Open {
+                container: Container::Array(elements),
+                ..
+            }) => elements.push(value),
+            Some(Open {
+                container: Container::Object { map, key },
+                ..
+            }) => {
+                map.insert(std::mem::take(key), value);
+            }
+            None => self.value = Some(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_complete;
+
+    #[test]
+    fn spans() {
+        let src = r#"{"a": [42, {"b/c": "x"}], "d": null, "e": {}}"#;
+        let (value, spans) = parse_spanned(src).unwrap();
+        assert_eq!(value, parse_complete(src).unwrap());
+
+        for (pointer, text) in [
+            ("", src),
+            ("/a", r#"[42, {"b/c": "x"}]"#),
+            ("/a/0", "42"),
+            ("/a/1", r#"{"b/c": "x"}"#),
+            ("/a/1/b~1c", r#""x""#),
+            ("/d", "null"),
+            ("/e", "{}"),
+        ] {
+            let span = spans.get(pointer).unwrap();
+            assert_eq!(&src[span], text, "{pointer}");
+        }
+        assert_eq!(spans.get("/a/0"), Some(7..9));
+        assert_eq!(spans.get("/a/2"), None);
+        assert_eq!(spans.get("/a/1/b/c"), None);
+
+        assert_eq!(spans.key("/a"), Some(1..4));
+        assert_eq!(&src[spans.key("/a/1/b~1c").unwrap()], r#""b/c""#);
+        assert_eq!(spans.key("/a/0"), None);
+        assert_eq!(spans.key(""), None);
+    }
+
+    #[test]
+    fn scalar_document() {
+        let (value, spans) = parse_spanned("\u{feff}  \"s\"\n").unwrap();
+        assert_eq!(value, Value::String("s".into()));
+        assert_eq!(spans.get(""), Some(5..8));
+    }
+
+    #[test]
+    fn errors() {
+        let error = parse_spanned("[1, {\"a\" 2}]").unwrap_err();
+        assert_eq!(error.message, "expected ':' after object key");
+        assert_eq!(error.byte_offset, 9);
+        assert!(parse_spanned("").is_err());
+        assert!(parse_spanned("1 2").is_err());
+    }
+}
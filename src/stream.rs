@@ -0,0 +1,228 @@
+use crate::{strip_bom, Context, ParseConfig, ParseError, Value};
+
+/// Parses `src` as a single JSON array, yielding its elements one at a time
+/// instead of collecting them into a [`Value::Array`].
+///
+/// Only the element being parsed is held in memory, so this suits arrays too
+/// large to hold at once, such as exports of many records. As with
+/// [`parse_complete`](crate::parse_complete), only whitespace may follow the
+/// closing `]`. The first error ends iteration.
+pub fn parse_array_stream(src: &str) -> ArrayStream<'_> {
+    ArrayStream::new(src)
+}
+
+/// Like [`parse_array_stream`], with `config` controlling what is accepted.
+/// The enclosing array counts towards [`ParseConfig::max_depth`], as it
+/// does for [`parse_complete`](crate::parse_complete).
+pub fn parse_array_stream_with_config(src: &str, config: ParseConfig) -> ArrayStream<'_> {
+    ArrayStream::with_config(src, config)
+}
+
+/// Iterator over the elements of a JSON array, see [`parse_array_stream`].
+#[derive(Debug, Clone)]
+pub struct ArrayStream<'a> {
+    src: &'a str,
+    config: ParseConfig,
+    cursor: Cursor<'a>,
+}
+
+/// Where an [`ArrayStream`] is in the array.
+#[derive(Debug, Clone)]
+struct Cursor<'a> {
+    /// The input not yet parsed.
+    remaining: &'a str,
+    state: State,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    /// The opening `[` comes next.
+    Start,
+    /// Just after the `[`, so either an element or `]` comes next.
+    FirstElement,
+    /// Just after an element, so a `,` or `]` comes next.
+    AfterElement,
+    /// The array ended, or there was an error.
+    Done,
+}
+
+impl<'a> ArrayStream<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self::with_config(src, ParseConfig::default())
+    }
+
+    /// Like [`ArrayStream::new`], with `config` controlling what is
+    /// accepted.
+    pub fn with_config(src: &'a str, config: ParseConfig) -> Self {
+        Self {
+            src,
+            config,
+            cursor: Cursor {
+                remaining: strip_bom(src),
+                state: State::Start,
+            },
+        }
+    }
+}
+
+impl<'a> Iterator for ArrayStream<'a> {
+    type Item = Result<Value<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let element = self
+            .cursor
+            .next_element(&Context::new(self.src, &self.config));
+        if !matches!(element, Ok(Some(_))) {
+            self.cursor.state = State::Done;
+        }
+        element.transpose()
+    }
+}
+
+impl<'a> Cursor<'a> {
+    fn next_element(&mut self, cx: &Context<'a, '_>) -> Result<Option<Value<'a>>, ParseError> {
+        if self.state == State::Start {
+            cx.check_input_size()?;
+        }
+        loop {
+            self.remaining = cx.skip_whitespace_and_comments(self.remaining)?;
+
+            match self.state {
+                State::Start => match self.remaining.strip_prefix('[') {
+                    Some(rest) => {
+                        cx.check_depth(self.remaining, 1)?;
+                        self.remaining = rest;
+                        self.state = State::FirstElement;
+                    }
+                    None => return Err(cx.error(self.remaining, "expected '['")),
+                },
+                State::FirstElement => match self.remaining.strip_prefix(']') {
+                    Some(rest) => return self.end(cx, rest),
+                    None => return self.element(cx).map(Some),
+                },
+                State::AfterElement => {
+                    if let Some(rest) = self.remaining.strip_prefix(',') {
+                        let rest = cx.skip_whitespace_and_comments(rest)?;
+                        if let Some(rest) = cx.strip_trailing_close(rest, ']') {
+                            return self.end(cx, rest);
+                        }
+                        self.remaining = rest;
+                        return self.element(cx).map(Some);
+                    } else if let Some(rest) = self.remaining.strip_prefix(']') {
+                        return self.end(cx, rest);
+                    } else {
+                        return Err(
+                            cx.error(self.remaining, "expected ',' or ']' after array element")
+                        );
+                    }
+                }
+                State::Done => return Ok(None),
+            }
+        }
+    }
+
+    fn element(&mut self, cx: &Context<'a, '_>) -> Result<Value<'a>, ParseError> {
+        let remaining = cx.skip_whitespace_and_comments(self.remaining)?;
+        let (value, rest) = cx.parse_nested_value(remaining, 1)?;
+        self.remaining = rest;
+        self.state = State::AfterElement;
+        Ok(value)
+    }
+
+    /// Checks that only whitespace follows the closing `]`, which `rest`
+    /// follows.
+    fn end(
+        &mut self,
+        cx: &Context<'a, '_>,
+        rest: &'a str,
+    ) -> Result<Option<Value<'a>>, ParseError> {
+        let rest = cx.skip_whitespace_and_comments(rest)?;
+        if !rest.is_empty() {
+            return Err(cx.error(rest, "unexpected trailing characters"));
+        }
+        self.state = State::Done;
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_complete, parse_with_config};
+
+    #[test]
+    fn elements() {
+        let src = r#" [1, {"a": [2, 3]}, "x", [], null] "#;
+        let elements: Vec<_> = parse_array_stream(src).map(Result::unwrap).collect();
+        assert_eq!(Value::Array(elements), parse_complete(src).unwrap());
+
+        assert_eq!(parse_array_stream("[]").count(), 0);
+        assert_eq!(parse_array_stream("\u{feff}[ ]\n").count(), 0);
+    }
+
+    #[test]
+    fn errors_end_iteration() {
+        for (src, message, offset) in [
+            ("", "expected '['", 0),
+            ("{}", "expected '['", 0),
+            ("[1 2]", "expected ',' or ']' after array element", 3),
+            ("[1,]", "unexpected character ']'", 3),
+            ("[1, 2", "expected ',' or ']' after array element", 5),
+            ("[1] 2", "unexpected trailing characters", 4),
+            ("[1, [2}]", "expected ',' or ']' after array element", 6),
+        ] {
+            let elements: Vec<_> = parse_array_stream(src).collect();
+            let error = elements.last().unwrap().as_ref().unwrap_err();
+            assert_eq!(
+                (error.message.as_str(), error.byte_offset),
+                (message, offset),
+                "{src}"
+            );
+            assert!(elements[..elements.len() - 1].iter().all(Result::is_ok));
+        }
+    }
+
+    #[test]
+    fn elements_before_an_error() {
+        let mut elements = parse_array_stream("[1, 2, x]");
+        assert_eq!(elements.next(), Some(Ok(Value::Integer(1))));
+        assert_eq!(elements.next(), Some(Ok(Value::Integer(2))));
+        assert!(elements.next().unwrap().is_err());
+        assert_eq!(elements.next(), None);
+    }
+
+    #[test]
+    fn depth_counts_the_enclosing_array() {
+        for max_depth in 0..4 {
+            let config = ParseConfig::new().max_depth(max_depth);
+            for src in ["[]", "[1]", "[[1]]", "[{\"a\": [1]}]"] {
+                let streamed = parse_array_stream_with_config(src, config.clone())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Value::Array);
+                let parsed = parse_with_config(src, &config).map(Option::unwrap);
+                assert_eq!(streamed, parsed, "{src} with max_depth {max_depth}");
+            }
+        }
+    }
+
+    #[test]
+    fn with_config() {
+        let config = ParseConfig::new()
+            .allow_comments(true)
+            .allow_trailing_commas(true);
+        let elements: Vec<_> = parse_array_stream_with_config("[1, /* two */ 2, ] // end", config)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(elements, [Value::Integer(1), Value::Integer(2)]);
+
+        let config = ParseConfig::new().max_input_bytes(4);
+        let mut elements = parse_array_stream_with_config("[1, 2]", config);
+        assert!(elements.next().unwrap().is_err());
+        assert_eq!(elements.next(), None);
+
+        let config = ParseConfig::new().max_string_bytes(1);
+        let elements: Vec<_> = parse_array_stream_with_config(r#"["a", "bc"]"#, config).collect();
+        assert_eq!(elements[0], Ok(Value::String("a".into())));
+        assert!(elements[1].is_err());
+    }
+}
@@ -0,0 +1,296 @@
+use std::borrow::Cow;
+
+use crate::{strip_bom, Context, ParseConfig, ParseError, Value};
+
+/// A token of a JSON document, see [`Tokenizer`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token<'a> {
+    BeginObject,
+    EndObject,
+    BeginArray,
+    EndArray,
+    /// An object key. The tokens of its value follow.
+    Key(Cow<'a, str>),
+    Null,
+    Bool(bool),
+    /// A number with a fraction or exponent, or outside the range of `i64`,
+    /// like [`Value::Number`].
+    Number(f64),
+    /// A number without a fraction or exponent, like [`Value::Integer`].
+    Integer(i64),
+    /// An integer outside the range of `i64`, like [`Value::BigInteger`].
+    BigInteger(i128),
+    /// A number as the text of its literal, with
+    /// [`ParseConfig::raw_numbers`], like [`Value::RawNumber`].
+    RawNumber(Cow<'a, str>),
+    String(Cow<'a, str>),
+}
+
+/// Iterates over the tokens of a single JSON document.
+///
+/// Accepts the same input as [`parse_complete`](crate::parse_complete), but
+/// yields the document one token at a time instead of building a [`Value`],
+/// for callers that build their own structures or only need some of it.
+/// After an error, iteration ends.
+#[derive(Debug, Clone)]
+pub struct Tokenizer<'a> {
+    src: &'a str,
+    config: ParseConfig,
+    cursor: Cursor<'a>,
+}
+
+/// Where a [`Tokenizer`] is in the document.
+#[derive(Debug, Clone)]
+struct Cursor<'a> {
+    /// The input not yet tokenized.
+    remaining: &'a str,
+    /// The arrays and objects that are open, `true` for objects.
+    stack: Vec<bool>,
+    state: State,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    /// A value starts next.
+    Value,
+    /// Just after a `[`, so either an element or `]` starts next.
+    FirstElement,
+    /// Just after a `{`, so either a key or `}` starts next.
+    FirstKey,
+    /// Just after a value, so a `,`, a closing bracket or the end of the
+    /// input comes next.
+    AfterValue,
+    /// The document ended, or there was an error.
+    Done,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self::with_config(src, ParseConfig::default())
+    }
+
+    /// Like [`Tokenizer::new`], with `config` controlling what is accepted.
+    /// [`ParseConfig::duplicate_key_behavior`] has no effect, every key is
+    /// yielded.
+    pub fn with_config(src: &'a str, config: ParseConfig) -> Self {
+        Self {
+            src,
+            config,
+            cursor: Cursor {
+                remaining: strip_bom(src),
+                stack: Vec::new(),
+                state: State::Value,
+            },
+        }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cursor.state == State::Done {
+            return None;
+        }
+        let cx = Context::new(self.src, &self.config);
+        let token = cx
+            .check_input_size()
+            .and_then(|()| self.cursor.next_token(&cx));
+        if !matches!(token, Ok(Some(_))) {
+            self.cursor.state = State::Done;
+        }
+        token.transpose()
+    }
+}
+
+impl<'a> Cursor<'a> {
+    fn next_token(&mut self, cx: &Context<'a, '_>) -> Result<Option<Token<'a>>, ParseError> {
+        loop {
+            self.remaining = cx.skip_whitespace_and_comments(self.remaining)?;
+
+            match self.state {
+                State::Value => return self.value(cx).map(Some),
+                State::FirstElement => match self.remaining.strip_prefix(']') {
+                    Some(rest) => return Ok(Some(self.close(rest))),
+                    None => self.state = State::Value,
+                },
+                State::FirstKey => match self.remaining.strip_prefix('}') {
+                    Some(rest) => return Ok(Some(self.close(rest))),
+                    None => return self.key(cx).map(Some),
+                },
+                State::AfterValue => return self.after_value(cx),
+                State::Done => return Ok(None),
+            }
+        }
+    }
+
+    fn value(&mut self, cx: &Context<'a, '_>) -> Result<Token<'a>, ParseError> {
+        match self.remaining.as_bytes().first() {
+            Some(b'[') => {
+                cx.check_depth(self.remaining, self.stack.len() + 1)?;
+                self.remaining = &self.remaining[1..];
+                self.stack.push(false);
+                self.state = State::FirstElement;
+                Ok(Token::BeginArray)
+            }
+            Some(b'{') => {
+                cx.check_depth(self.remaining, self.stack.len() + 1)?;
+                self.remaining = &self.remaining[1..];
+                self.stack.push(true);
+                self.state = State::FirstKey;
+                Ok(Token::BeginObject)
+            }
+            _ => {
+                let (value, rest) = cx.parse_scalar(self.remaining)?;
+                self.remaining = rest;
+                self.state = State::AfterValue;
+                Ok(match value {
+                    Value::Null => Token::Null,
+                    Value::Bool(b) => Token::Bool(b),
+                    Value::Number(n) => Token::Number(n),
+                    Value::Integer(n) => Token::Integer(n),
+                    Value::BigInteger(n) => Token::BigInteger(n),
+                    Value::RawNumber(raw) => Token::RawNumber(raw),
+                    Value::String(s) => Token::String(s),
+                    Value::Array(_) | Value::Object(_) => {
+                        unreachable!("parse_scalar doesn't parse containers")
+                    }
+                })
+            }
+        }
+    }
+
+    fn key(&mut self, cx: &Context<'a, '_>) -> Result<Token<'a>, ParseError> {
+        let (key, _, rest) = cx.parse_key(self.remaining)?;
+        self.remaining = rest;
+        self.state = State::Value;
+        Ok(Token::Key(key))
+    }
+
+    /// Closes the innermost container, whose bracket `rest` follows.
+    fn close(&mut self, rest: &'a str) -> Token<'a> {
+        self.remaining = rest;
+        self.state = State::AfterValue;
+        match self.stack.pop() {
+            Some(true) => Token::EndObject,
+            _ => Token::EndArray,
+        }
+    }
+
+    fn after_value(&mut self, cx: &Context<'a, '_>) -> Result<Option<Token<'a>>, ParseError> {
+        let Some(&is_object) = self.stack.last() else {
+            if !self.remaining.is_empty() {
+                return Err(cx.error(self.remaining, "unexpected trailing characters"));
+            }
+            self.state = State::Done;
+            return Ok(None);
+        };
+        let (close, expected) = match is_object {
+            true => ('}', "expected ',' or '}' after object value"),
+            false => (']', "expected ',' or ']' after array element"),
+        };
+
+        if let Some(rest) = self.remaining.strip_prefix(',') {
+            self.remaining = cx.skip_whitespace_and_comments(rest)?;
+            if let Some(rest) = cx.strip_trailing_close(self.remaining, close) {
+                return Ok(Some(self.close(rest)));
+            }
+            if is_object {
+                return self.key(cx).map(Some);
+            }
+            self.state = State::Value;
+            self.value(cx).map(Some)
+        } else if let Some(rest) = self.remaining.strip_prefix(close) {
+            Ok(Some(self.close(rest)))
+        } else {
+            Err(cx.error(self.remaining, expected))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    fn tokens(src: &str) -> Vec<Result<Token<'_>, ParseError>> {
+        Tokenizer::new(src).collect()
+    }
+
+    #[test]
+    fn document() {
+        use Token::*;
+
+        let src = r#" {"a": [1, 2.5, {}], "b\n": {"c": null}, "d": [], "e": "f"} "#;
+        let expected = [
+            BeginObject,
+            Key("a".into()),
+            BeginArray,
+            Integer(1),
+            Number(2.5),
+            BeginObject,
+            EndObject,
+            EndArray,
+            Key("b\n".into()),
+            BeginObject,
+            Key("c".into()),
+            Null,
+            EndObject,
+            Key("d".into()),
+            BeginArray,
+            EndArray,
+            Key("e".into()),
+            String("f".into()),
+            EndObject,
+        ];
+        assert_eq!(tokens(src), expected.map(Ok));
+        assert_eq!(tokens("false"), [Ok(Bool(false))]);
+        assert_eq!(
+            tokens("[[]]"),
+            [BeginArray, BeginArray, EndArray, EndArray].map(Ok)
+        );
+    }
+
+    #[test]
+    fn errors_end_iteration() {
+        for (src, message, offset) in [
+            ("", "unexpected end of input", 0),
+            ("[1 2]", "expected ',' or ']' after array element", 3),
+            ("{\"a\": 1,}", "expected string key", 8),
+            ("[1] x", "unexpected trailing characters", 4),
+            ("[", "unexpected end of input", 1),
+        ] {
+            let tokens = tokens(src);
+            let error = tokens.last().unwrap().as_ref().unwrap_err();
+            assert_eq!(
+                (error.message.as_str(), error.byte_offset),
+                (message, offset),
+                "{src}"
+            );
+            assert!(tokens[..tokens.len() - 1].iter().all(Result::is_ok));
+        }
+
+        let deep = "[".repeat(200);
+        let error = tokens(&deep).pop().unwrap().unwrap_err();
+        assert_eq!(error.kind, ErrorKind::DepthLimitExceeded);
+    }
+
+    #[test]
+    fn with_config() {
+        let config = ParseConfig::new()
+            .allow_comments(true)
+            .allow_trailing_commas(true);
+        let tokens: Vec<_> = Tokenizer::with_config("[1, /* two */ 2,] // end", config)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            tokens,
+            [
+                Token::BeginArray,
+                Token::Integer(1),
+                Token::Integer(2),
+                Token::EndArray
+            ]
+        );
+    }
+}
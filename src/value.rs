@@ -0,0 +1,1321 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::pointer::array_index;
+use crate::Map;
+
+/// A parsed JSON value.
+///
+/// Values are `Eq` and `Hash`, so they can be stored in sets. Objects are
+/// equal when they have the same entries in any order. A `NaN` number equals
+/// itself, and `0.0` equals `-0.0`. Numbers and integers never equal each
+/// other, so `1.0` is not `1`.
+#[derive(Debug, Clone)]
+pub enum Value<'a> {
+    Null,
+    Bool(bool),
+    /// A number with a fraction or exponent, or an integer outside the range
+    /// of `i64`.
+    Number(f64),
+    /// A number without a fraction or exponent that fits in an `i64`.
+    Integer(i64),
+    /// A number without a fraction or exponent that is outside the range of
+    /// `i64` but fits in an `i128`. Only parsed with the `i128` feature;
+    /// larger integers, up to `u128::MAX` and beyond, are still a
+    /// [`Value::Number`].
+    BigInteger(i128),
+    /// A number kept as the text of its literal, as parsed with
+    /// [`ParseConfig::raw_numbers`](crate::ParseConfig::raw_numbers).
+    /// Serializing writes the text back unchanged.
+    RawNumber(Cow<'a, str>),
+    /// A string with its escape sequences decoded. Borrows from the input
+    /// unless it contained escapes.
+    String(Cow<'a, str>),
+    Array(Vec<Value<'a>>),
+    Object(Map<Cow<'a, str>, Value<'a>>),
+}
+
+impl<'a> Value<'a> {
+    /// Returns `true` if `self` is [`Value::Null`].
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Returns `true` if `self` is [`Value::Bool`].
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Self::Bool(_))
+    }
+
+    /// Returns `true` if `self` is a [`Value::Number`], [`Value::Integer`],
+    /// [`Value::RawNumber`] or `Value::BigInteger`.
+    pub fn is_number(&self) -> bool {
+        self.type_name() == "number"
+    }
+
+    /// Returns `true` if `self` is [`Value::String`].
+    pub fn is_string(&self) -> bool {
+        matches!(self, Self::String(_))
+    }
+
+    /// Returns `true` if `self` is [`Value::Array`].
+    pub fn is_array(&self) -> bool {
+        matches!(self, Self::Array(_))
+    }
+
+    /// Returns `true` if `self` is [`Value::Object`].
+    pub fn is_object(&self) -> bool {
+        matches!(self, Self::Object(_))
+    }
+
+    /// Returns the name of the JSON type of `self`: `"null"`, `"bool"`,
+    /// `"number"`, `"string"`, `"array"` or `"object"`. Integers are numbers
+    /// too.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool(_) => "bool",
+            Self::Number(_) | Self::Integer(_) | Self::RawNumber(_) => "number",
+            Self::BigInteger(_) => "number",
+            Self::String(_) => "string",
+            Self::Array(_) => "array",
+            Self::Object(_) => "object",
+        }
+    }
+
+    /// Returns whether JavaScript would treat `self` as true: everything
+    /// except `null`, `false`, zero, NaN and the empty string. Empty arrays
+    /// and objects are truthy.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::Null => false,
+            Self::Bool(b) => *b,
+            Self::Number(n) => *n != 0.0 && !n.is_nan(),
+            Self::Integer(n) => *n != 0,
+            Self::BigInteger(n) => *n != 0,
+            Self::RawNumber(raw) => parse_raw_number(raw).is_truthy(),
+            Self::String(s) => !s.is_empty(),
+            Self::Array(_) | Self::Object(_) => true,
+        }
+    }
+
+    /// Returns the boolean of a [`Value::Bool`].
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns the numeric value of a [`Value::Number`], [`Value::Integer`]
+    /// or [`Value::RawNumber`], and `None` for every other variant.
+    ///
+    /// Integers beyond 2^53 lose precision in the conversion.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            Self::Integer(n) => Some(*n as f64),
+            Self::BigInteger(n) => Some(*n as f64),
+            Self::RawNumber(raw) => parse_raw_number(raw).as_f64(),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer of a [`Value::Integer`], of a
+    /// `Value::BigInteger`, or of a [`Value::RawNumber`] without a fraction
+    /// or exponent, and `None` for every other variant.
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Self::Integer(n) => Some((*n).into()),
+            Self::BigInteger(n) => Some(*n),
+            Self::RawNumber(raw) if !raw.contains(['.', 'e', 'E']) => raw.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the literal of a [`Value::RawNumber`], exactly as it was
+    /// written in the input.
+    pub fn raw_number(&self) -> Option<&str> {
+        match self {
+            Self::RawNumber(raw) => Some(raw),
+            _ => None,
+        }
+    }
+
+    /// Returns the contents of a [`Value::String`].
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements of a [`Value::Array`].
+    pub fn as_array(&self) -> Option<&Vec<Value<'a>>> {
+        match self {
+            Self::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements of a [`Value::Array`] mutably.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value<'a>>> {
+        match self {
+            Self::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// Returns the entries of a [`Value::Object`].
+    pub fn as_object(&self) -> Option<&Map<Cow<'a, str>, Value<'a>>> {
+        match self {
+            Self::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Returns the value for `key` if `self` is an object containing it.
+    pub fn get(&self, key: &str) -> Option<&Value<'a>> {
+        self.as_object()?.get(key)
+    }
+
+    /// Returns the element at `index` if `self` is an array long enough to
+    /// have it.
+    pub fn get_index(&self, index: usize) -> Option<&Value<'a>> {
+        self.as_array()?.get(index)
+    }
+
+    /// Returns the value for `key` like [`Value::get`], or `default` if
+    /// there is none.
+    pub fn get_or<'v>(&'v self, key: &str, default: &'v Value<'a>) -> &'v Value<'a> {
+        self.get(key).unwrap_or(default)
+    }
+
+    /// Returns `true` if `self` is an object containing `key`, whatever its
+    /// value.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.as_object().is_some_and(|map| map.contains_key(key))
+    }
+
+    /// Returns `true` if `self` is an array with an element equal to
+    /// `value`.
+    pub fn contains(&self, value: &Value) -> bool {
+        self.as_array()
+            .is_some_and(|arr| arr.iter().any(|e| e == value))
+    }
+
+    /// Follows the segments of `path` through nested objects and arrays,
+    /// returning `None` at the first one that doesn't exist.
+    ///
+    /// `path` is a slice of segments such as `&["servers", "0", "name"]`, or
+    /// a string that separates them with dots, `"servers.0.name"`. A dotted
+    /// path can't reach keys that contain a `.` themselves, use a slice or
+    /// [`Value::pointer`] for those.
+    ///
+    /// A segment indexes an array if `self` is one at that point, and must
+    /// then be a decimal index such as `"0"`; otherwise it is an object key.
+    pub fn get_path(&self, path: impl KeyPath) -> Option<&Value<'a>> {
+        path.segments()
+            .try_fold(self, |value, segment| match value {
+                Self::Object(map) => map.get(segment),
+                Self::Array(arr) => arr.get(array_index(segment)?),
+                _ => None,
+            })
+    }
+
+    /// Returns the entries of a [`Value::Object`] mutably.
+    pub fn as_object_mut(&mut self) -> Option<&mut Map<Cow<'a, str>, Value<'a>>> {
+        match self {
+            Self::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Inserts an entry into an object, returning the value `key` had
+    /// before. Does nothing and returns `None` if `self` is not an object.
+    pub fn insert(&mut self, key: impl Into<Cow<'a, str>>, value: Value<'a>) -> Option<Value<'a>> {
+        self.as_object_mut()?.insert(key.into(), value)
+    }
+
+    /// Appends an element to an array. Does nothing if `self` is not an
+    /// array.
+    pub fn push(&mut self, value: Value<'a>) {
+        if let Some(arr) = self.as_array_mut() {
+            arr.push(value);
+        }
+    }
+
+    /// Removes `key` from an object and returns its value. Returns `None` if
+    /// there is no such key or `self` is not an object.
+    pub fn remove(&mut self, key: &str) -> Option<Value<'a>> {
+        self.as_object_mut()?.remove(key)
+    }
+
+    /// Removes the last element of an array and returns it. Returns `None`
+    /// if the array is empty or `self` is not an array.
+    pub fn pop(&mut self) -> Option<Value<'a>> {
+        self.as_array_mut()?.pop()
+    }
+
+    /// Keeps only the elements of an array for which `f` returns `true`.
+    /// Does nothing if `self` is not an array.
+    pub fn retain(&mut self, f: impl FnMut(&Value<'a>) -> bool) {
+        if let Some(arr) = self.as_array_mut() {
+            arr.retain(f);
+        }
+    }
+
+    /// Keeps only the entries of an object for which `f` returns `true`.
+    /// Does nothing if `self` is not an object.
+    pub fn retain_entries(&mut self, mut f: impl FnMut(&str, &Value<'a>) -> bool) {
+        if let Some(map) = self.as_object_mut() {
+            map.retain(|k, v| f(k, v));
+        }
+    }
+
+    /// Calls `visit` on `self` and every value nested in it, depth-first in
+    /// pre-order: a container is visited before its elements, and each
+    /// element before its next sibling. Object entries are visited in the
+    /// map's iteration order.
+    pub fn walk<'v>(&'v self, visit: &mut impl FnMut(&'v Value<'a>)) {
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            visit(value);
+            let start = stack.len();
+            match value {
+                Self::Array(arr) => stack.extend(arr),
+                Self::Object(map) => stack.extend(map.values()),
+                _ => {}
+            }
+            stack[start..].reverse();
+        }
+    }
+
+    /// Like [`Value::walk`], with mutable access to every value. Changes
+    /// `visit` makes to a value are seen when its elements are visited next.
+    pub fn walk_mut(&mut self, visit: &mut impl FnMut(&mut Value<'a>)) {
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            visit(value);
+            let start = stack.len();
+            match value {
+                Self::Array(arr) => stack.extend(arr),
+                Self::Object(map) => stack.extend(map.values_mut()),
+                _ => {}
+            }
+            stack[start..].reverse();
+        }
+    }
+
+    /// Returns a copy of an array with the elements of nested arrays
+    /// inlined one level deep, like JavaScript's `Array.prototype.flat()`:
+    /// `[[1, 2], [3, [4]], 5]` becomes `[1, 2, 3, [4], 5]`.
+    ///
+    /// Returns a copy of `self` if it is not an array.
+    pub fn flatten(&self) -> Value<'a> {
+        let Self::Array(arr) = self else {
+            return self.clone();
+        };
+
+        let mut flat = Vec::with_capacity(arr.len());
+        for element in arr {
+            match element {
+                Self::Array(inner) => flat.extend(inner.iter().cloned()),
+                _ => flat.push(element.clone()),
+            }
+        }
+        Self::Array(flat)
+    }
+
+    /// Like [`Value::flatten`], but inlines arrays at any depth, so no
+    /// element of the result is an array: `[[1, 2], [3, [4]], 5]` becomes
+    /// `[1, 2, 3, 4, 5]`. Arrays inside objects are left alone.
+    pub fn flatten_deep(&self) -> Value<'a> {
+        fn flatten_into<'a>(arr: &[Value<'a>], flat: &mut Vec<Value<'a>>) {
+            for element in arr {
+                match element {
+                    Value::Array(inner) => flatten_into(inner, flat),
+                    _ => flat.push(element.clone()),
+                }
+            }
+        }
+
+        let Self::Array(arr) = self else {
+            return self.clone();
+        };
+        let mut flat = Vec::new();
+        flatten_into(arr, &mut flat);
+        Self::Array(flat)
+    }
+
+    /// Returns the number of elements of an array or entries of an object,
+    /// and `None` for every other value.
+    ///
+    /// Strings return `None` too, as their length could mean bytes or
+    /// characters. Use [`Value::as_str`] to get at either.
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Self::Array(arr) => Some(arr.len()),
+            Self::Object(map) => Some(map.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` for an empty array or object, that is when
+    /// [`Value::len`] is `Some(0)`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// Sorts the keys of every object in `self`, including nested ones, see
+    /// [`Map::sort_keys`].
+    pub fn sort_keys(&mut self) {
+        match self {
+            Self::Array(arr) => arr.iter_mut().for_each(Value::sort_keys),
+            Self::Object(map) => {
+                map.sort_keys();
+                map.values_mut().for_each(Value::sort_keys);
+            }
+            _ => {}
+        }
+    }
+
+    /// Iterates over the keys and values of an object, or over nothing if
+    /// `self` is not an object.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Value<'a>)> {
+        self.as_object()
+            .into_iter()
+            .flatten()
+            .map(|(k, v)| (k.as_ref(), v))
+    }
+
+    /// Iterates over the elements of an array, or over nothing if `self` is
+    /// not an array. Same as iterating over `&self`.
+    pub fn iter_array(&self) -> std::slice::Iter<'_, Value<'a>> {
+        self.into_iter()
+    }
+
+    /// Same as [`Value::entries`].
+    pub fn iter_object(&self) -> impl Iterator<Item = (&str, &Value<'a>)> {
+        self.entries()
+    }
+
+    /// Consumes an array and iterates over its elements, or over nothing if
+    /// `self` is not an array.
+    pub fn into_array(self) -> std::vec::IntoIter<Value<'a>> {
+        match self {
+            Self::Array(arr) => arr.into_iter(),
+            _ => Vec::new().into_iter(),
+        }
+    }
+
+    /// Consumes an object and iterates over its entries, or over nothing if
+    /// `self` is not an object.
+    pub fn into_object(self) -> impl Iterator<Item = (Cow<'a, str>, Value<'a>)> {
+        match self {
+            Self::Object(map) => Some(map),
+            _ => None,
+        }
+        .into_iter()
+        .flatten()
+    }
+
+    /// Iterates over the keys of an object, or over nothing if `self` is
+    /// not an object.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries().map(|(k, _)| k)
+    }
+
+    /// Iterates over the values of an object, or over nothing if `self` is
+    /// not an object.
+    pub fn values(&self) -> impl Iterator<Item = &Value<'a>> {
+        self.entries().map(|(_, v)| v)
+    }
+
+    /// Returns the entries of an object sorted by key, comparing keys by
+    /// Unicode scalar values, or an empty `Vec` if `self` is not an object.
+    ///
+    /// Unlike [`Value::sort_keys`], this leaves `self` alone and gives the
+    /// same order with every map representation.
+    pub fn entries_sorted(&self) -> Vec<(&str, &Value<'a>)> {
+        let mut entries: Vec<_> = self.entries().collect();
+        // `str` compares by UTF-8 bytes, which orders like scalar values.
+        entries.sort_unstable_by_key(|&(k, _)| k);
+        entries
+    }
+}
+
+/// Iterates over the elements of an array, or over nothing if `self` is not
+/// an array.
+impl<'v, 'a> IntoIterator for &'v Value<'a> {
+    type Item = &'v Value<'a>;
+    type IntoIter = std::slice::Iter<'v, Value<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Value::Array(arr) => arr.iter(),
+            _ => [].iter(),
+        }
+    }
+}
+
+/// Looks up `key` in an object.
+///
+/// # Panics
+///
+/// Panics if `self` is not an object or has no entry for `key`, like
+/// indexing a `Map` does. Chains such as `value["a"]["b"]` panic at the
+/// first missing step rather than yielding `Null`. Use [`Value::get`] to
+/// handle both cases.
+impl<'a> Index<&str> for Value<'a> {
+    type Output = Value<'a>;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self {
+            Self::Object(map) => match map.get(key) {
+                Some(value) => value,
+                None => panic!("no entry found for key {key:?}"),
+            },
+            _ => panic!("cannot index into a non-object value with key {key:?}"),
+        }
+    }
+}
+
+impl IndexMut<&str> for Value<'_> {
+    fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+        match self {
+            Self::Object(map) => match map.get_mut(key) {
+                Some(value) => value,
+                None => panic!("no entry found for key {key:?}"),
+            },
+            _ => panic!("cannot index into a non-object value with key {key:?}"),
+        }
+    }
+}
+
+/// Returns the element at `index` of an array.
+///
+/// # Panics
+///
+/// Panics if `self` is not an array or `index` is out of bounds. Use
+/// [`Value::get_index`] to handle both cases.
+impl<'a> Index<usize> for Value<'a> {
+    type Output = Value<'a>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self {
+            Self::Array(arr) => &arr[index],
+            _ => panic!("cannot index into a non-array value with {index}"),
+        }
+    }
+}
+
+impl IndexMut<usize> for Value<'_> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match self {
+            Self::Array(arr) => &mut arr[index],
+            _ => panic!("cannot index into a non-array value with {index}"),
+        }
+    }
+}
+
+impl From<bool> for Value<'_> {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+impl From<i32> for Value<'_> {
+    fn from(n: i32) -> Self {
+        Self::Integer(n.into())
+    }
+}
+
+impl From<u32> for Value<'_> {
+    fn from(n: u32) -> Self {
+        Self::Integer(n.into())
+    }
+}
+
+impl From<i64> for Value<'_> {
+    fn from(n: i64) -> Self {
+        Self::Integer(n)
+    }
+}
+
+impl From<f64> for Value<'_> {
+    fn from(n: f64) -> Self {
+        Self::Number(n)
+    }
+}
+
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(s: &'a str) -> Self {
+        Self::String(Cow::Borrowed(s))
+    }
+}
+
+/// Owns the string, so the result can be a `Value<'static>`.
+impl From<String> for Value<'_> {
+    fn from(s: String) -> Self {
+        Self::String(Cow::Owned(s))
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Value<'a> {
+    fn from(s: Cow<'a, str>) -> Self {
+        Self::String(s)
+    }
+}
+
+impl<'a> From<Vec<Value<'a>>> for Value<'a> {
+    fn from(elements: Vec<Value<'a>>) -> Self {
+        Self::Array(elements)
+    }
+}
+
+impl<'a> From<Map<Cow<'a, str>, Value<'a>>> for Value<'a> {
+    fn from(map: Map<Cow<'a, str>, Value<'a>>) -> Self {
+        Self::Object(map)
+    }
+}
+
+impl<'a> From<HashMap<&'a str, Value<'a>>> for Value<'a> {
+    fn from(map: HashMap<&'a str, Value<'a>>) -> Self {
+        Self::Object(
+            map.into_iter()
+                .map(|(k, v)| (Cow::Borrowed(k), v))
+                .collect(),
+        )
+    }
+}
+
+/// `None` becomes `Null`.
+impl<'a, T: Into<Value<'a>>> From<Option<T>> for Value<'a> {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+/// Collects into an array.
+impl<'a, T: Into<Value<'a>>> FromIterator<T> for Value<'a> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+/// A path of object keys and array indices for [`Value::get_path`].
+pub trait KeyPath {
+    fn segments(&self) -> impl Iterator<Item = &str>;
+}
+
+impl KeyPath for &[&str] {
+    fn segments(&self) -> impl Iterator<Item = &str> {
+        self.iter().copied()
+    }
+}
+
+impl<const N: usize> KeyPath for &[&str; N] {
+    fn segments(&self) -> impl Iterator<Item = &str> {
+        self.iter().copied()
+    }
+}
+
+impl KeyPath for &Vec<&str> {
+    fn segments(&self) -> impl Iterator<Item = &str> {
+        self.iter().copied()
+    }
+}
+
+/// Segments separated by `.`. The empty string is the empty path.
+impl KeyPath for &str {
+    fn segments(&self) -> impl Iterator<Item = &str> {
+        (!self.is_empty())
+            .then(|| self.split('.'))
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// Error returned when a [`Value`] can't be converted into a Rust type with
+/// `TryFrom`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TryFromValueError {
+    message: &'static str,
+    /// The [`Value::type_name`] of a value of the wrong type.
+    found: Option<&'static str>,
+}
+
+impl TryFromValueError {
+    fn new(message: &'static str) -> Self {
+        Self {
+            message,
+            found: None,
+        }
+    }
+
+    /// An error for `value` not being of the type described by `expected`.
+    fn expected(expected: &'static str, value: &Value) -> Self {
+        Self {
+            message: expected,
+            found: Some(value.type_name()),
+        }
+    }
+}
+
+impl fmt::Display for TryFromValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message)?;
+        match self.found {
+            Some(found) => write!(f, ", found {found}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for TryFromValueError {}
+
+impl TryFrom<Value<'_>> for bool {
+    type Error = TryFromValueError;
+
+    fn try_from(value: Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bool(b) => Ok(b),
+            value => Err(TryFromValueError::expected("expected a boolean", &value)),
+        }
+    }
+}
+
+/// Accepts a `Number` only if it is an integer within the range of `i64`.
+impl TryFrom<Value<'_>> for i64 {
+    type Error = TryFromValueError;
+
+    fn try_from(value: Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Integer(n) => Ok(n),
+            Value::Number(n) => exact_i64(n).ok_or(TryFromValueError::new(
+                "number is not an integer that fits in i64",
+            )),
+            Value::BigInteger(_) => Err(TryFromValueError::new(
+                "number is not an integer that fits in i64",
+            )),
+            Value::RawNumber(raw) => Self::try_from(parse_raw_number(&raw)),
+            value => Err(TryFromValueError::expected("expected a number", &value)),
+        }
+    }
+}
+
+/// Accepts an `Integer` only if an `f64` can represent it exactly.
+impl TryFrom<Value<'_>> for f64 {
+    type Error = TryFromValueError;
+
+    fn try_from(value: Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(n) => Ok(n),
+            Value::Integer(n) if exact_i64(n as f64) == Some(n) => Ok(n as f64),
+            Value::Integer(_) => Err(TryFromValueError::new(
+                "integer is not exactly representable as f64",
+            )),
+            Value::BigInteger(n) if n as f64 as i128 == n => Ok(n as f64),
+            Value::BigInteger(_) => Err(TryFromValueError::new(
+                "integer is not exactly representable as f64",
+            )),
+            Value::RawNumber(raw) => Self::try_from(parse_raw_number(&raw)),
+            value => Err(TryFromValueError::expected("expected a number", &value)),
+        }
+    }
+}
+
+/// Returns the [`Value::Integer`] or [`Value::Number`] that the number
+/// literal `raw` parses as by default.
+pub(crate) fn parse_raw_number(raw: &str) -> Value<'static> {
+    if !raw.contains(['.', 'e', 'E']) {
+        if let Some(integer) = parse_integer(raw) {
+            return integer;
+        }
+    }
+    Value::Number(raw.parse().unwrap_or(f64::NAN))
+}
+
+/// Parses an integer literal as a [`Value::Integer`], or as a
+/// [`Value::BigInteger`] with the `i128` feature. Returns `None` if it is out
+/// of range.
+pub(crate) fn parse_integer(literal: &str) -> Option<Value<'static>> {
+    if let Ok(n) = literal.parse() {
+        return Some(Value::Integer(n));
+    }
+    #[cfg(feature = "i128")]
+    if let Ok(n) = literal.parse() {
+        return Some(Value::BigInteger(n));
+    }
+    None
+}
+
+/// Returns `n` as an `i64` if it is an integer in range.
+fn exact_i64(n: f64) -> Option<i64> {
+    // -2^63 and 2^63, which are exact in an `f64`.
+    const MIN: f64 = i64::MIN as f64;
+    const MAX: f64 = -MIN;
+    (n.fract() == 0.0 && (MIN..MAX).contains(&n)).then_some(n as i64)
+}
+
+impl TryFrom<Value<'_>> for String {
+    type Error = TryFromValueError;
+
+    fn try_from(value: Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(s) => Ok(s.into_owned()),
+            value => Err(TryFromValueError::expected("expected a string", &value)),
+        }
+    }
+}
+
+impl<'a> TryFrom<Value<'a>> for Vec<Value<'a>> {
+    type Error = TryFromValueError;
+
+    fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Array(elements) => Ok(elements),
+            value => Err(TryFromValueError::expected("expected an array", &value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn as_f64() {
+        assert_eq!(Value::Number(1.5).as_f64(), Some(1.5));
+        assert_eq!(Value::Integer(-3).as_f64(), Some(-3.0));
+        assert_eq!(Value::String("1".into()).as_f64(), None);
+    }
+
+    #[test]
+    fn accessors() {
+        assert_eq!(Value::Bool(false).as_bool(), Some(false));
+        assert_eq!(Value::Null.as_bool(), None);
+        assert_eq!(Value::String("s".into()).as_str(), Some("s"));
+        assert_eq!(Value::Integer(1).as_str(), None);
+        assert_eq!(
+            Value::Array(vec![Value::Null]).as_array(),
+            Some(&vec![Value::Null])
+        );
+        assert_eq!(Value::Null.as_array(), None);
+        assert_eq!(
+            Value::Object(Map::from([("k".into(), Value::Null)])).as_object(),
+            Some(&Map::from([("k".into(), Value::Null)]))
+        );
+        assert_eq!(Value::Array(vec![]).as_object(), None);
+    }
+
+    #[test]
+    fn get() {
+        let value = Value::Object(Map::from([(
+            "a".into(),
+            Value::Array(vec![Value::Integer(1), Value::Null]),
+        )]));
+
+        assert_eq!(
+            value.get("a").and_then(|a| a.get_index(0)),
+            Some(&Value::Integer(1))
+        );
+        assert_eq!(value.get("b"), None);
+        assert_eq!(value.get_index(0), None);
+        assert_eq!(value["a"].get("a"), None);
+        assert_eq!(value["a"].get_index(2), None);
+    }
+
+    #[test]
+    fn get_on_other_variants() {
+        for value in [
+            Value::Null,
+            Value::Bool(true),
+            Value::Number(1.0),
+            Value::Integer(1),
+            Value::String("a".into()),
+            Value::Array(vec![Value::String("a".into())]),
+        ] {
+            assert_eq!(value.get("a"), None);
+        }
+        assert_eq!(
+            Value::Object(Map::from([("0".into(), Value::Null)])).get_index(0),
+            None
+        );
+    }
+
+    #[test]
+    fn index() {
+        let mut value = Value::Object(Map::from([(
+            "a".into(),
+            Value::Object(Map::from([(
+                "b".into(),
+                Value::Array(vec![Value::Bool(true)]),
+            )])),
+        )]));
+
+        assert_eq!(value["a"]["b"][0], Value::Bool(true));
+
+        value["a"]["b"][0] = Value::Null;
+        assert_eq!(value["a"]["b"][0], Value::Null);
+    }
+
+    #[test]
+    #[should_panic(expected = "no entry found for key \"missing\"")]
+    fn index_missing_key() {
+        let _ = &Value::Object(Map::new())["missing"];
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot index into a non-object value with key \"a\"")]
+    fn index_non_object() {
+        let _ = &Value::Array(vec![])["a"];
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot index into a non-array value with 0")]
+    fn index_non_array() {
+        let _ = &Value::Null[0];
+    }
+
+    #[test]
+    fn mutate() {
+        let mut value = Value::Object(Map::new());
+        assert_eq!(value.insert("a", Value::Array(vec![])), None);
+        assert_eq!(
+            value.insert(String::from("a"), Value::Array(vec![])),
+            Some(Value::Array(vec![]))
+        );
+
+        value["a"].push(Value::Integer(1));
+        value["a"].push(Value::Null);
+        assert_eq!(value["a"].pop(), Some(Value::Null));
+        assert_eq!(value["a"], Value::Array(vec![Value::Integer(1)]));
+
+        assert_eq!(value.pop(), None);
+        assert_eq!(value["a"].remove("a"), None);
+        assert_eq!(value.remove("b"), None);
+        assert_eq!(
+            value.remove("a"),
+            Some(Value::Array(vec![Value::Integer(1)]))
+        );
+        assert!(value.is_empty());
+    }
+
+    #[test]
+    fn retain() {
+        let mut value = crate::parse_complete(r#"[1, null, 2, "x", null]"#).unwrap();
+        value.retain(|e| !e.is_null());
+        assert_eq!(value, crate::parse_complete(r#"[1, 2, "x"]"#).unwrap());
+
+        let mut value = crate::parse_complete(r#"{"a": 1, "_b": 2, "c": null}"#).unwrap();
+        value.retain_entries(|k, v| !k.starts_with('_') && !v.is_null());
+        assert_eq!(value, crate::parse_complete(r#"{"a": 1}"#).unwrap());
+
+        let mut scalar = Value::Integer(1);
+        scalar.retain(|_| false);
+        scalar.retain_entries(|_, _| false);
+        assert_eq!(scalar, Value::Integer(1));
+    }
+
+    #[test]
+    fn walk() {
+        let mut value =
+            crate::parse_complete(r#"[1, ["a", [true]], {"k": "b"}, {"password": "x"}, "c"]"#)
+                .unwrap();
+
+        let mut names = Vec::new();
+        value.walk(&mut |v| names.push(v.type_name()));
+        assert_eq!(
+            names,
+            [
+                "array", "number", "array", "string", "array", "bool", "object", "string",
+                "object", "string", "string"
+            ]
+        );
+
+        let mut strings = Vec::new();
+        value.walk(&mut |v| strings.extend(v.as_str()));
+        assert_eq!(strings, ["a", "b", "x", "c"]);
+
+        value.walk_mut(&mut |v| {
+            if let Some(password) = v.as_object_mut().and_then(|map| map.get_mut("password")) {
+                *password = Value::from("***");
+            }
+        });
+        assert_eq!(value[3]["password"], Value::from("***"));
+
+        // Elements replaced by `visit` are walked, not the ones they replaced.
+        let mut value = Value::Array(vec![Value::Null]);
+        let mut count = 0;
+        value.walk_mut(&mut |v| {
+            count += 1;
+            if v.is_null() {
+                *v = Value::Array(vec![Value::Integer(1), Value::Integer(2)]);
+            }
+        });
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn flatten() {
+        let parse = |src| crate::parse_complete(src).unwrap();
+        let value = parse(r#"[[1, 2], [3, [4, [5]]], 6, [], {"a": [7]}]"#);
+
+        assert_eq!(
+            value.flatten(),
+            parse(r#"[1, 2, 3, [4, [5]], 6, {"a": [7]}]"#)
+        );
+        assert_eq!(
+            value.flatten_deep(),
+            parse(r#"[1, 2, 3, 4, 5, 6, {"a": [7]}]"#)
+        );
+        assert_eq!(
+            value,
+            parse(r#"[[1, 2], [3, [4, [5]]], 6, [], {"a": [7]}]"#)
+        );
+
+        for other in [parse(r#"{"a": [[1]]}"#), Value::Null, Value::from("s")] {
+            assert_eq!(other.flatten(), other);
+            assert_eq!(other.flatten_deep(), other);
+        }
+    }
+
+    #[test]
+    fn insert_and_push_on_other_types() {
+        let mut value = Value::Array(vec![]);
+        assert_eq!(value.insert("a", Value::Null), None);
+        assert_eq!(value, Value::Array(vec![]));
+
+        let mut value = Value::Null;
+        value.push(Value::Null);
+        assert_eq!(value, Value::Null);
+    }
+
+    #[test]
+    fn accessors_on_every_variant() {
+        let values = [
+            Value::Null,
+            Value::Bool(true),
+            Value::Number(0.5),
+            Value::Integer(2),
+            Value::String("s".into()),
+            Value::Array(vec![Value::Null]),
+            Value::Object(Map::from([("k".into(), Value::Null)])),
+        ];
+
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(value.is_null(), i == 0);
+            assert_eq!(value.as_bool(), (i == 1).then_some(true));
+            assert_eq!(
+                value.as_f64(),
+                match i {
+                    2 => Some(0.5),
+                    3 => Some(2.0),
+                    _ => None,
+                }
+            );
+            assert_eq!(value.as_str(), (i == 4).then_some("s"));
+            assert_eq!(value.as_array().is_some(), i == 5);
+            assert_eq!(value.as_object().is_some(), i == 6);
+
+            assert_eq!(value.is_bool(), i == 1);
+            assert_eq!(value.is_number(), i == 2 || i == 3);
+            assert_eq!(value.is_string(), i == 4);
+            assert_eq!(value.is_array(), i == 5);
+            assert_eq!(value.is_object(), i == 6);
+            assert_eq!(value.is_truthy(), i != 0);
+        }
+    }
+
+    #[test]
+    fn falsy_values() {
+        for value in [
+            Value::Bool(false),
+            Value::Number(0.0),
+            Value::Number(-0.0),
+            Value::Number(f64::NAN),
+            Value::Integer(0),
+            Value::String("".into()),
+        ] {
+            assert!(!value.is_truthy(), "{value:?}");
+        }
+        assert!(Value::Array(vec![]).is_truthy());
+        assert!(Value::Object(Map::new()).is_truthy());
+        assert!(Value::String("0".into()).is_truthy());
+    }
+
+    #[test]
+    fn mutable_accessors() {
+        let mut arr = Value::Array(vec![]);
+        arr.as_array_mut().unwrap().push(Value::Null);
+        assert_eq!(arr, Value::Array(vec![Value::Null]));
+        assert_eq!(arr.as_object_mut(), None);
+
+        let mut obj = Value::Object(Map::new());
+        obj.as_object_mut()
+            .unwrap()
+            .insert("k".into(), Value::Bool(true));
+        assert_eq!(
+            obj,
+            Value::Object(Map::from([("k".into(), Value::Bool(true))]))
+        );
+        assert_eq!(obj.as_array_mut(), None);
+    }
+
+    #[test]
+    fn from_rust_types() {
+        let value = Value::from(HashMap::from([
+            ("name", Value::from("parse-that-json")),
+            ("version", Value::from(1)),
+            ("score", Value::from(4.5)),
+            ("stable", Value::from(true)),
+            ("tags", ["json", "parser"].into_iter().collect()),
+            ("homepage", Value::from(None::<&str>)),
+            ("owner", Value::from(Some(String::from("stinta")))),
+            ("deps", Value::from(vec![Value::from(HashMap::new())])),
+        ]));
+
+        assert_eq!(
+            value,
+            Value::Object(Map::from([
+                ("name".into(), Value::String("parse-that-json".into())),
+                ("version".into(), Value::Integer(1)),
+                ("score".into(), Value::Number(4.5)),
+                ("stable".into(), Value::Bool(true)),
+                (
+                    "tags".into(),
+                    Value::Array(vec![
+                        Value::String("json".into()),
+                        Value::String("parser".into())
+                    ])
+                ),
+                ("homepage".into(), Value::Null),
+                ("owner".into(), Value::String("stinta".into())),
+                ("deps".into(), Value::Array(vec![Value::Object(Map::new())])),
+            ]))
+        );
+        assert_eq!(Value::from(i64::MIN), Value::Integer(i64::MIN));
+        assert_eq!(Value::from(u32::MAX), Value::Integer(u32::MAX.into()));
+    }
+
+    #[test]
+    fn try_into_rust_types() {
+        assert_eq!(bool::try_from(Value::Bool(true)), Ok(true));
+        assert_eq!(i64::try_from(Value::Integer(-7)), Ok(-7));
+        assert_eq!(i64::try_from(Value::Number(3.0)), Ok(3));
+        assert_eq!(
+            i64::try_from(Value::Number(-9.223372036854776e18)),
+            Ok(i64::MIN)
+        );
+        assert_eq!(f64::try_from(Value::Number(0.5)), Ok(0.5));
+        assert_eq!(f64::try_from(Value::Integer(1 << 60)), Ok(2f64.powi(60)));
+        assert_eq!(String::try_from(Value::from("s")), Ok("s".to_owned()));
+        assert_eq!(
+            Vec::try_from(Value::Array(vec![Value::Null])),
+            Ok(vec![Value::Null])
+        );
+    }
+
+    #[test]
+    fn try_into_rust_types_errors() {
+        let message = |error: TryFromValueError| error.to_string();
+
+        assert_eq!(
+            bool::try_from(Value::Null).map_err(message),
+            Err("expected a boolean, found null".into())
+        );
+        assert_eq!(
+            String::try_from(Value::Integer(1)).map_err(message),
+            Err("expected a string, found number".into())
+        );
+        assert_eq!(
+            Vec::try_from(Value::Null).map_err(message),
+            Err("expected an array, found null".into())
+        );
+        assert_eq!(
+            i64::try_from(Value::from("1")).map_err(message),
+            Err("expected a number, found string".into())
+        );
+        for n in [0.5, 9.223372036854776e18, f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                i64::try_from(Value::Number(n)).map_err(message),
+                Err("number is not an integer that fits in i64".into())
+            );
+        }
+        assert_eq!(
+            f64::try_from(Value::Integer(i64::MAX)).map_err(message),
+            Err("integer is not exactly representable as f64".into())
+        );
+    }
+
+    #[test]
+    fn type_name() {
+        let value = crate::parse_complete(r#"[null, true, 1, 1.5, "s", [], {}]"#).unwrap();
+        let names: Vec<_> = value.into_iter().map(Value::type_name).collect();
+        assert_eq!(
+            names,
+            ["null", "bool", "number", "number", "string", "array", "object"]
+        );
+    }
+
+    #[test]
+    fn iterate() {
+        let arr = Value::Array(vec![Value::Integer(1), Value::Null]);
+        let mut seen = vec![];
+        for element in &arr {
+            seen.push(element);
+        }
+        assert_eq!(seen, [&Value::Integer(1), &Value::Null]);
+        assert_eq!(arr.entries().count(), 0);
+
+        let obj = Value::Object(Map::from([("k".into(), Value::Bool(true))]));
+        assert_eq!(
+            obj.entries().collect::<Vec<_>>(),
+            [("k", &Value::Bool(true))]
+        );
+        assert_eq!(obj.keys().collect::<Vec<_>>(), ["k"]);
+        assert_eq!(obj.values().collect::<Vec<_>>(), [&Value::Bool(true)]);
+        assert_eq!((&obj).into_iter().count(), 0);
+        assert_eq!((&Value::Null).into_iter().count(), 0);
+        assert_eq!(arr.keys().count() + arr.values().count(), 0);
+
+        let unsorted =
+            crate::parse_complete(r#"{"b": 1, "é": 2, "a": 3, "B": 4, "z": 5}"#).unwrap();
+        let keys: Vec<_> = unsorted
+            .entries_sorted()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, ["B", "a", "b", "z", "é"]);
+        assert_eq!(unsorted.entries_sorted()[1], ("a", &Value::Integer(3)));
+        assert!(arr.entries_sorted().is_empty());
+
+        assert_eq!(arr.iter_array().collect::<Vec<_>>(), seen);
+        assert_eq!(
+            obj.iter_object().collect::<Vec<_>>(),
+            obj.entries().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            obj.clone().into_object().collect::<Vec<_>>(),
+            [("k".into(), Value::Bool(true))]
+        );
+        assert_eq!(
+            arr.clone().into_array().collect::<Vec<_>>(),
+            [Value::Integer(1), Value::Null]
+        );
+
+        for scalar in [Value::Null, Value::from("s"), Value::Integer(3)] {
+            assert_eq!(scalar.iter_array().count(), 0);
+            assert_eq!(scalar.iter_object().count(), 0);
+            assert_eq!(scalar.clone().into_array().count(), 0);
+            assert_eq!(scalar.into_object().count(), 0);
+        }
+        assert_eq!(obj.into_array().count(), 0);
+        assert_eq!(arr.into_object().count(), 0);
+    }
+
+    #[test]
+    fn len() {
+        let cases = [
+            (Value::Null, None, false),
+            (Value::Bool(false), None, false),
+            (Value::Number(0.0), None, false),
+            (Value::Integer(3), None, false),
+            (Value::String("h\u{e9}".into()), None, false),
+            (Value::String("".into()), None, false),
+            (Value::Array(vec![Value::Null, Value::Null]), Some(2), false),
+            (Value::Array(vec![]), Some(0), true),
+            (
+                Value::Object(Map::from([("k".into(), Value::Null)])),
+                Some(1),
+                false,
+            ),
+            (Value::Object(Map::new()), Some(0), true),
+        ];
+        for (value, len, is_empty) in cases {
+            assert_eq!(
+                (value.len(), value.is_empty()),
+                (len, is_empty),
+                "{value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn contains() {
+        let value = crate::parse_complete(r#"{"a": null, "b": [1, "x", {"c": 2}]}"#).unwrap();
+
+        assert!(value.contains_key("a"));
+        assert!(!value.contains_key("c"));
+        assert!(!value["b"].contains_key("0"));
+
+        assert!(value["b"].contains(&Value::from("x")));
+        assert!(value["b"].contains(&value["b"][2]));
+        assert!(!value["b"].contains(&Value::Number(1.0)));
+        assert!(!value.contains(&Value::Null));
+        assert!(!Value::from("x").contains(&Value::from("x")));
+
+        let fallback = Value::Integer(0);
+        assert_eq!(value.get_or("a", &fallback), &Value::Null);
+        assert_eq!(value.get_or("z", &fallback), &fallback);
+        assert_eq!(value["b"].get_or("a", &fallback), &fallback);
+    }
+
+    #[test]
+    fn get_path() {
+        let value = crate::parse_complete(
+            r#"{"servers": [{"name": "a", "ports": [80, 443]}], "0": {"1": true}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value.get_path(&[]), Some(&value));
+        assert_eq!(
+            value.get_path(&["servers", "0", "name"]),
+            Some(&Value::from("a"))
+        );
+        assert_eq!(
+            value.get_path(&["servers", "0", "ports", "1"]),
+            Some(&Value::Integer(443))
+        );
+        assert_eq!(value.get_path(&["0", "1"]), Some(&Value::Bool(true)));
+        for path in [
+            &["servers", "1"][..],
+            &["servers", "01"],
+            &["servers", "name"],
+            &["servers", "0", "name", "x"],
+            &["missing"],
+        ] {
+            assert_eq!(value.get_path(path), None, "{path:?}");
+        }
+    }
+
+    #[test]
+    fn get_dotted_path() {
+        let value = crate::parse_complete(
+            r#"{"server": {"ports": [80, {"tls": true}]}, "a.b": 1, "": {"": 2}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value.get_path(""), Some(&value));
+        assert_eq!(value.get_path("server.ports.0"), Some(&Value::Integer(80)));
+        assert_eq!(
+            value.get_path("server.ports.1.tls"),
+            Some(&Value::Bool(true))
+        );
+        assert_eq!(
+            value.get_path("server.ports"),
+            value.get_path(&["server", "ports"])
+        );
+        for path in ["server.ports.2", "server.port", "server.ports.x", "a.b"] {
+            assert_eq!(value.get_path(path), None, "{path}");
+        }
+
+        // Keys with dots need a slice. Empty keys are empty segments.
+        assert_eq!(value.get_path(&["a.b"]), Some(&Value::Integer(1)));
+        assert_eq!(value.get_path(&vec!["a.b"]), Some(&Value::Integer(1)));
+        assert_eq!(value.get_path("."), Some(&Value::Integer(2)));
+    }
+}
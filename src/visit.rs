@@ -0,0 +1,235 @@
+use crate::value::parse_raw_number;
+use crate::Value;
+
+/// Callbacks for [`traverse`], one per kind of node.
+///
+/// Every method does nothing by default, so implementations only need the
+/// ones they care about.
+pub trait Visitor {
+    fn visit_null(&mut self) {}
+
+    fn visit_bool(&mut self, _b: bool) {}
+
+    /// Called for [`Value::Number`], and for [`Value::Integer`] unless
+    /// [`Visitor::visit_integer`] is overridden.
+    fn visit_number(&mut self, _n: f64) {}
+
+    /// Called for [`Value::Integer`]. Passes it on to
+    /// [`Visitor::visit_number`] by default, which is lossy beyond 2^53.
+    fn visit_integer(&mut self, n: i64) {
+        self.visit_number(n as f64);
+    }
+
+    /// Called for [`Value::BigInteger`]. Passes it on to
+    /// [`Visitor::visit_number`] by default.
+    fn visit_big_integer(&mut self, n: i128) {
+        self.visit_number(n as f64);
+    }
+
+    fn visit_string(&mut self, _s: &str) {}
+
+    /// Called before the `len` elements of an array.
+    fn visit_array_start(&mut self, _len: usize) {}
+
+    fn visit_array_end(&mut self) {}
+
+    /// Called before the `len` entries of an object.
+    fn visit_object_start(&mut self, _len: usize) {}
+
+    /// Called before the value of each object entry.
+    fn visit_key(&mut self, _k: &str) {}
+
+    fn visit_object_end(&mut self) {}
+}
+
+/// Walks `value` depth-first, calling the method of `visitor` for each node
+/// in document order.
+///
+/// Object entries come in the map's iteration order. Deeply nested values
+/// don't use up the call stack.
+pub fn traverse(value: &Value, visitor: &mut impl Visitor) {
+    enum Step<'v, 'a> {
+        Value(&'v Value<'a>),
+        Key(&'v str),
+        ArrayEnd,
+        ObjectEnd,
+    }
+
+    let mut stack = vec![Step::Value(value)];
+    while let Some(step) = stack.pop() {
+        match step {
+            Step::Value(value) => match value {
+                Value::Null => visitor.visit_null(),
+                Value::Bool(b) => visitor.visit_bool(*b),
+                Value::Number(n) => visitor.visit_number(*n),
+                Value::Integer(n) => visitor.visit_integer(*n),
+                Value::BigInteger(n) => visitor.visit_big_integer(*n),
+                Value::RawNumber(raw) => match parse_raw_number(raw) {
+                    Value::Integer(n) => visitor.visit_integer(n),
+                    Value::BigInteger(n) => visitor.visit_big_integer(n),
+                    number => visitor.visit_number(number.as_f64().unwrap_or(f64::NAN)),
+                },
+                Value::String(s) => visitor.visit_string(s),
+                Value::Array(arr) => {
+                    visitor.visit_array_start(arr.len());
+                    stack.push(Step::ArrayEnd);
+                    stack.extend(arr.iter().rev().map(Step::Value));
+                }
+                Value::Object(map) => {
+                    visitor.visit_object_start(map.len());
+                    stack.push(Step::ObjectEnd);
+                    let start = stack.len();
+                    for (k, v) in map {
+                        stack.push(Step::Key(k));
+                        stack.push(Step::Value(v));
+                    }
+                    stack[start..].reverse();
+                }
+            },
+            Step::Key(k) => visitor.visit_key(k),
+            Step::ArrayEnd => visitor.visit_array_end(),
+            Step::ObjectEnd => visitor.visit_object_end(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_complete;
+
+    /// Writes back compact JSON, which checks the order of the calls.
+    #[derive(Default)]
+    struct Printer {
+        out: String,
+        /// Whether the next element needs a `,` in front, per open container.
+        comma: Vec<bool>,
+    }
+
+    impl Printer {
+        fn element(&mut self, text: &str) {
+            if let Some(comma) = self.comma.last_mut() {
+                if *comma {
+                    self.out.push(',');
+                }
+                *comma = true;
+            }
+            self.out.push_str(text);
+        }
+    }
+
+    impl Visitor for Printer {
+        fn visit_null(&mut self) {
+            self.element("null");
+        }
+
+        fn visit_bool(&mut self, b: bool) {
+            self.element(&b.to_string());
+        }
+
+        fn visit_number(&mut self, n: f64) {
+            self.element(&format!("{n:?}"));
+        }
+
+        fn visit_integer(&mut self, n: i64) {
+            self.element(&n.to_string());
+        }
+
+        fn visit_string(&mut self, s: &str) {
+            self.element(&format!("{s:?}"));
+        }
+
+        fn visit_array_start(&mut self, _len: usize) {
+            self.element("[");
+            self.comma.push(false);
+        }
+
+        fn visit_array_end(&mut self) {
+            self.comma.pop();
+            self.out.push(']');
+        }
+
+        fn visit_object_start(&mut self, _len: usize) {
+            self.element("{");
+            self.comma.push(false);
+        }
+
+        fn visit_key(&mut self, k: &str) {
+            self.element(&format!("{k:?}:"));
+            // The value follows the key without a comma.
+            *self.comma.last_mut().unwrap() = false;
+        }
+
+        fn visit_object_end(&mut self) {
+            self.comma.pop();
+            self.out.push('}');
+        }
+    }
+
+    #[test]
+    fn traversal_order() {
+        let src = r#"[1,{"a":[true,null,2.5]},[],{},"s",[[{"b":{"c":-3}}]]]"#;
+        let mut printer = Printer::default();
+        traverse(&parse_complete(src).unwrap(), &mut printer);
+        assert_eq!(printer.out, src);
+    }
+
+    #[test]
+    fn default_methods() {
+        #[derive(Default)]
+        struct Numbers {
+            sum: f64,
+            lens: Vec<usize>,
+        }
+
+        impl Visitor for Numbers {
+            fn visit_number(&mut self, n: f64) {
+                self.sum += n;
+            }
+
+            fn visit_array_start(&mut self, len: usize) {
+                self.lens.push(len);
+            }
+
+            fn visit_object_start(&mut self, len: usize) {
+                self.lens.push(len);
+            }
+        }
+
+        let mut numbers = Numbers::default();
+        let value = parse_complete(r#"{"a": [1, 2.5, "3"], "b": {"c": 4, "d": null}}"#).unwrap();
+        traverse(&value, &mut numbers);
+        assert_eq!(numbers.sum, 7.5);
+        numbers.lens.sort_unstable();
+        assert_eq!(numbers.lens, [2, 2, 3]);
+    }
+
+    #[test]
+    fn deep_nesting() {
+        struct Depth(usize, usize);
+
+        impl Visitor for Depth {
+            fn visit_array_start(&mut self, _len: usize) {
+                self.0 += 1;
+                self.1 = self.1.max(self.0);
+            }
+
+            fn visit_array_end(&mut self) {
+                self.0 -= 1;
+            }
+        }
+
+        let mut value = Value::Null;
+        for _ in 0..100_000 {
+            value = Value::Array(vec![value]);
+        }
+        let mut depth = Depth(0, 0);
+        traverse(&value, &mut depth);
+        assert_eq!((depth.0, depth.1), (0, 100_000));
+
+        // Dropping is recursive, so take the nesting apart first.
+        while let Value::Array(mut arr) = value {
+            value = arr.pop().unwrap_or(Value::Null);
+        }
+    }
+}
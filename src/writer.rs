@@ -0,0 +1,247 @@
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+
+use crate::Value;
+
+/// Writes one JSON document piece by piece, without building a [`Value`]
+/// for all of it first.
+///
+/// The output is compact, like [`Value::to_json_string`], and commas and
+/// colons are written as needed. Output goes straight to any
+/// [`fmt::Write`], such as a `String` that was allocated up front.
+///
+/// # Panics
+///
+/// Every method panics when called out of order, e.g. a value inside an
+/// object without a key before it, or a second top-level value.
+#[derive(Debug)]
+pub struct JsonWriter<W> {
+    out: W,
+    stack: Vec<Open>,
+    /// Whether the top-level value is complete.
+    done: bool,
+}
+
+/// An array or object that has been begun but not ended.
+#[derive(Debug)]
+struct Open {
+    object: bool,
+    /// Whether nothing has been written inside it yet.
+    empty: bool,
+    /// Whether a key has been written whose value hasn't.
+    has_key: bool,
+}
+
+impl<W: Write> JsonWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            stack: Vec::new(),
+            done: false,
+        }
+    }
+
+    pub fn begin_object(&mut self) -> fmt::Result {
+        self.begin(true)
+    }
+
+    /// Writes the key of the next object entry, whose value must follow.
+    pub fn write_key(&mut self, key: &str) -> fmt::Result {
+        let open = match self.stack.last_mut() {
+            Some(open) if open.object && !open.has_key => open,
+            Some(open) if open.object => {
+                panic!("expected a value after the key, found key {key:?}")
+            }
+            _ => panic!("cannot write key {key:?} outside of an object"),
+        };
+        if !open.empty {
+            self.out.write_char(',')?;
+        }
+        open.empty = false;
+        open.has_key = true;
+        write!(self.out, "{}:", Value::String(Cow::Borrowed(key)))
+    }
+
+    pub fn end_object(&mut self) -> fmt::Result {
+        self.end(true)
+    }
+
+    pub fn begin_array(&mut self) -> fmt::Result {
+        self.begin(false)
+    }
+
+    /// Writes `value` as the next element of an array. Unlike
+    /// [`JsonWriter::write_value`], this panics outside of an array.
+    pub fn write_element(&mut self, value: &Value) -> fmt::Result {
+        assert!(
+            self.stack.last().is_some_and(|open| !open.object),
+            "cannot write an element outside of an array"
+        );
+        self.write_value(value)
+    }
+
+    pub fn end_array(&mut self) -> fmt::Result {
+        self.end(false)
+    }
+
+    /// Writes a whole value: the top-level value, the value after a key, or
+    /// the next array element.
+    pub fn write_value(&mut self, value: &Value) -> fmt::Result {
+        self.before_value()?;
+        write!(self.out, "{value}")?;
+        self.after_value();
+        Ok(())
+    }
+
+    pub fn write_null(&mut self) -> fmt::Result {
+        self.write_value(&Value::Null)
+    }
+
+    pub fn write_bool(&mut self, b: bool) -> fmt::Result {
+        self.write_value(&Value::Bool(b))
+    }
+
+    /// Writes a number like [`Value::Number`]: non-finite numbers become
+    /// `null`.
+    pub fn write_number(&mut self, n: f64) -> fmt::Result {
+        self.write_value(&Value::Number(n))
+    }
+
+    pub fn write_integer(&mut self, n: i64) -> fmt::Result {
+        self.write_value(&Value::Integer(n))
+    }
+
+    pub fn write_string(&mut self, s: &str) -> fmt::Result {
+        self.write_value(&Value::String(Cow::Borrowed(s)))
+    }
+
+    /// Returns the output.
+    ///
+    /// # Panics
+    ///
+    /// If no value was written, or an array or object is still open.
+    pub fn finish(self) -> W {
+        assert!(self.done, "cannot finish an incomplete document");
+        self.out
+    }
+
+    fn begin(&mut self, object: bool) -> fmt::Result {
+        self.before_value()?;
+        self.stack.push(Open {
+            object,
+            empty: true,
+            has_key: false,
+        });
+        self.out.write_char(if object { '{' } else { '[' })
+    }
+
+    fn end(&mut self, object: bool) -> fmt::Result {
+        let name = if object { "object" } else { "array" };
+        match self.stack.pop() {
+            Some(open) if open.object == object && !open.has_key => {}
+            Some(open) if open.has_key => panic!("cannot end an object after a key"),
+            _ => panic!("cannot end an {name} that isn't open"),
+        }
+        self.out.write_char(if object { '}' } else { ']' })?;
+        self.after_value();
+        Ok(())
+    }
+
+    /// Checks that a value may come next, writing a `,` if needed.
+    fn before_value(&mut self) -> fmt::Result {
+        match self.stack.last_mut() {
+            None => assert!(!self.done, "a JSON document has only one top-level value"),
+            Some(open) if open.object => {
+                assert!(open.has_key, "expected a key before an object value");
+                open.has_key = false;
+            }
+            Some(open) => {
+                if !open.empty {
+                    self.out.write_char(',')?;
+                }
+                open.empty = false;
+            }
+        }
+        Ok(())
+    }
+
+    fn after_value(&mut self) {
+        if self.stack.is_empty() {
+            self.done = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_complete;
+
+    #[test]
+    fn nested_document() {
+        let mut writer = JsonWriter::new(String::new());
+        writer.begin_array().unwrap();
+        writer.write_null().unwrap();
+        writer.write_number(2.5).unwrap();
+        writer.write_number(f64::NAN).unwrap();
+        writer.begin_object().unwrap();
+        writer.end_object().unwrap();
+        writer.begin_object().unwrap();
+        writer.write_key("a\"b").unwrap();
+        writer.begin_array().unwrap();
+        writer.end_array().unwrap();
+        writer.write_key("c").unwrap();
+        writer.write_string("d\n").unwrap();
+        writer.end_object().unwrap();
+        writer
+            .write_element(&parse_complete(r#"{"e": [false]}"#).unwrap())
+            .unwrap();
+        writer.end_array().unwrap();
+
+        let out = writer.finish();
+        assert_eq!(
+            out,
+            r#"[null,2.5,null,{},{"a\"b":[],"c":"d\n"},{"e":[false]}]"#
+        );
+        assert!(parse_complete(&out).is_ok());
+    }
+
+    #[test]
+    fn scalar_document() {
+        let mut writer = JsonWriter::new(String::new());
+        writer.write_bool(true).unwrap();
+        assert_eq!(writer.finish(), "true");
+    }
+
+    #[test]
+    #[should_panic = "expected a key before an object value"]
+    fn value_without_key() {
+        let mut writer = JsonWriter::new(String::new());
+        writer.begin_object().unwrap();
+        let _ = writer.write_integer(1);
+    }
+
+    #[test]
+    #[should_panic = "cannot end an array that isn't open"]
+    fn mismatched_end() {
+        let mut writer = JsonWriter::new(String::new());
+        writer.begin_object().unwrap();
+        let _ = writer.end_array();
+    }
+
+    #[test]
+    #[should_panic = "only one top-level value"]
+    fn second_document() {
+        let mut writer = JsonWriter::new(String::new());
+        writer.write_null().unwrap();
+        let _ = writer.write_null();
+    }
+
+    #[test]
+    #[should_panic = "cannot finish an incomplete document"]
+    fn unfinished() {
+        let mut writer = JsonWriter::new(String::new());
+        writer.begin_array().unwrap();
+        writer.finish();
+    }
+}